@@ -1,12 +1,28 @@
 // CLIコマンド定義
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use chrono::NaiveDate;
+use clap_complete::engine::ArgValueCandidates;
+use std::path::PathBuf;
+use crate::completion;
+use crate::config::StorageBackend;
+use crate::data;
+use crate::export::{ExportFormat, ExportKind, Granularity};
+use crate::report::{MarkdownStyle, ReportFormat};
 
 #[derive(Parser, Debug)]
 #[command(name = "time-checker")]
 #[command(about = "シンプルな作業時間記録ツール", long_about = None)]
 #[command(version)]
 pub struct Cli {
+    /// データディレクトリ（環境変数 TIME_CHECKER_DATA_DIR や config.toml より優先）
+    #[arg(long, global = true, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+
+    /// 結果をJSONで出力（スクリプトから使う場合、エラーも標準エラー出力にJSONで出す）
+    #[arg(long, global = true)]
+    pub json: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -16,23 +32,313 @@ pub enum Commands {
     /// タスクを開始（進行中のタスクがあれば自動終了）
     Start {
         /// タスク名
+        #[arg(add = ArgValueCandidates::new(completion::task_candidates))]
         task: String,
 
+        /// タグ（例: +backend +urgent）
+        #[arg(value_name = "+TAG", value_parser = parse_tag_arg)]
+        tags: Vec<String>,
+
         /// 備考・メモ（オプション）
         #[arg(short, long)]
         note: Option<String>,
+
+        /// 開始時刻（HH:MM または YYYY-MM-DD HH:MM、省略時は現在時刻）
+        #[arg(long, value_name = "TIME", conflicts_with = "ago")]
+        at: Option<String>,
+
+        /// 何分前に開始したか（例: 10m, 1h30m）
+        #[arg(long, value_name = "DURATION")]
+        ago: Option<String>,
     },
 
     /// 現在のタスクを停止して今日のサマリーを表示
-    Stop,
+    Stop {
+        /// 停止時刻（HH:MM または YYYY-MM-DD HH:MM、省略時は現在時刻）
+        #[arg(long, value_name = "TIME", conflicts_with = "ago")]
+        at: Option<String>,
+
+        /// 何分前に停止したか（例: 10m, 1h30m）
+        #[arg(long, value_name = "DURATION")]
+        ago: Option<String>,
+    },
+
+    /// 以前のタスクをタスク名・メモを引き継いで開始（省略時は最近のタスクから選ぶ）
+    #[command(alias = "restart")]
+    Continue {
+        /// 何番目に最近のタスクか（1 が直前に終了したタスク）
+        #[arg(value_name = "N", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        nth: Option<usize>,
+    },
+
+    /// 現在のタスクを一時停止（resume で同じタスク名・メモのまま再開できる）
+    Pause,
+
+    /// 一時停止中のタスクを再開
+    Resume,
+
+    /// 開始・終了時刻を指定して終了済みのエントリを追加
+    Add {
+        /// タスク名
+        #[arg(add = ArgValueCandidates::new(completion::task_candidates))]
+        task: String,
+
+        /// タグ（例: +backend +urgent）
+        #[arg(value_name = "+TAG", value_parser = parse_tag_arg)]
+        tags: Vec<String>,
+
+        /// 開始時刻（HH:MM または YYYY-MM-DD HH:MM）
+        #[arg(long, value_name = "TIME")]
+        from: String,
+
+        /// 終了時刻（HH:MM または YYYY-MM-DD HH:MM、開始時刻以前の HH:MM は翌日とみなす）
+        #[arg(long, value_name = "TIME")]
+        to: String,
+
+        /// 日付（YYYY-MM-DD、省略時は今日）
+        #[arg(long, value_name = "DATE")]
+        date: Option<NaiveDate>,
+
+        /// 備考・メモ（オプション）
+        #[arg(short, long)]
+        note: Option<String>,
+    },
 
     /// 現在のタスクと今日のサマリーを表示
     Status,
 
     /// 指定期間のサマリーを表示（デフォルトは今日）
+    #[command(alias = "report")]
     Show {
-        /// 期間（省略時は今日）
+        /// 期間（today, yesterday, week, last-week, month, last-month, YYYY-MM-DD, YYYY-MM-DD..YYYY-MM-DD）
+        #[arg(default_value = "today")]
+        period: String,
+
+        /// 集計から除外するタスク（カンマ区切りまたは複数回指定、設定ファイルの exclude_tasks の代わりに使う）
+        #[arg(long, value_name = "TASKS")]
+        exclude: Vec<String>,
+
+        /// 除外設定を無視してすべてのタスクを集計
+        #[arg(long, conflicts_with = "exclude")]
+        no_exclude: bool,
+
+        /// 指定したタグ（カンマ区切りで複数指定、いずれかを持つエントリ）だけを集計
+        #[arg(long = "tag", value_delimiter = ',', value_name = "TAGS", value_parser = parse_tag_option)]
+        tags: Vec<String>,
+
+        /// タスクごとではなくタグごとに集計
+        #[arg(long)]
+        by_tag: bool,
+
+        /// `/` で区切ったタスク名（clientA/設計/レビュー）を階層として小計つきで表示
+        #[arg(long, conflicts_with = "by_tag")]
+        tree: bool,
+
+        /// 階層を指定した深さまでに畳んで表示（--tree を含む）
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with = "by_tag")]
+        depth: Option<u32>,
+
+        /// 出力形式（markdown は日報などに貼り付ける用、--by-tag / --tree とは併用できない）
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+
+        /// Markdownでのタスクの並べ方（config.toml の report.style の代わりに使う）
+        #[arg(long, value_enum)]
+        style: Option<MarkdownStyle>,
+
+        /// Markdownで各タスクの下にメモを含める（config.toml の report.notes の代わりに使う）
+        #[arg(long)]
+        notes: bool,
+
+        /// Markdownにメモを含めない（config.toml の report.notes = true を打ち消す）
+        #[arg(long, conflicts_with = "notes")]
+        no_notes: bool,
+    },
+
+    /// 指定期間のエントリを個別に表示（デフォルトは今日）
+    List {
+        /// 期間（show と同じ指定方法）
+        #[arg(default_value = "today")]
+        period: String,
+
+        /// 指定したタグ（カンマ区切りで複数指定、いずれかを持つエントリ）だけを表示
+        #[arg(long = "tag", value_delimiter = ',', value_name = "TAGS", value_parser = parse_tag_option)]
+        tags: Vec<String>,
+    },
+
+    /// エントリまたは集計結果をCSV（RFC 4180）またはiCalendarで出力
+    Export {
+        /// 出力する内容
+        #[arg(value_enum)]
+        kind: ExportKind,
+
+        /// 期間（show と同じ指定方法）
+        #[arg(default_value = "today")]
+        period: String,
+
+        /// 出力形式（ics は entries のみ）
+        #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+        format: ExportFormat,
+
+        /// 集計の単位（summary のみ）
+        #[arg(long, value_enum, default_value_t = Granularity::Day)]
+        per: Granularity,
+
+        /// 指定したタグ（カンマ区切りで複数指定、いずれかを持つエントリ）だけを出力
+        #[arg(long = "tag", value_delimiter = ',', value_name = "TAGS", value_parser = parse_tag_option)]
+        tags: Vec<String>,
+
+        /// 除外設定を無視してすべてのタスクを集計（summary のみ）
+        #[arg(long)]
+        no_exclude: bool,
+
+        /// 出力先のファイル（省略時は標準出力）
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+
+        /// 先頭に BOM を付ける（Excel で文字化けさせないため）
+        #[arg(long)]
+        bom: bool,
+    },
+
+    /// カレンダーの予定を終了済みのエントリとして取り込む（既存のエントリと重なる予定は取り込まない）
+    Import {
+        /// 読み込むファイル
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// 期間（show と同じ指定方法、予定の開始日で判定）
         #[arg(default_value = "today")]
         period: String,
+
+        /// ファイルの形式
+        #[arg(long, value_enum, default_value_t = ImportFormat::Ics)]
+        format: ImportFormat,
+
+        /// 件名がいずれかのパターンに一致する予定だけを取り込む（カンマ区切りまたは複数回指定、exclude_tasks と同じ書き方）
+        #[arg(long = "match", value_name = "PATTERNS")]
+        patterns: Vec<String>,
+
+        /// 取り込んだエントリに付けるタグ（カンマ区切り）
+        #[arg(long = "tag", value_delimiter = ',', value_name = "TAGS", value_parser = parse_tag_option)]
+        tags: Vec<String>,
+
+        /// 保存せずに取り込まれる予定を表示
+        #[arg(long)]
+        dry_run: bool,
     },
+
+    /// 記録済みのエントリを編集
+    Edit {
+        /// エントリのID（list で確認できる）
+        id: u64,
+
+        /// 新しいタスク名
+        #[arg(long)]
+        task: Option<String>,
+
+        /// 新しいメモ（空文字でメモを削除）
+        #[arg(short, long)]
+        note: Option<String>,
+
+        /// 新しい開始時刻（HH:MM または YYYY-MM-DD HH:MM）
+        #[arg(long)]
+        start: Option<String>,
+
+        /// 新しい終了時刻（HH:MM または YYYY-MM-DD HH:MM）
+        #[arg(long)]
+        end: Option<String>,
+
+        /// 新しいタグ（カンマ区切り、空文字でタグを削除）
+        #[arg(long, value_name = "TAGS")]
+        tags: Option<String>,
+    },
+
+    /// 記録済みのエントリを削除
+    Delete {
+        /// エントリのID（list で確認できる）
+        id: u64,
+    },
+
+    /// 直前の変更操作（start / stop / pause / resume / add / edit / delete）を取り消す
+    Undo {
+        /// 取り消す操作の数
+        #[arg(default_value_t = 1, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        count: usize,
+    },
+
+    /// 取り消した操作をやり直す
+    Redo {
+        /// やり直す操作の数
+        #[arg(default_value_t = 1, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        count: usize,
+    },
+
+    /// 保存形式の間でデータを移行（json / sqlite / journal）
+    Migrate {
+        /// 移行先の形式
+        #[arg(value_enum)]
+        to: StorageBackend,
+
+        /// 移行元の形式（省略時は config.toml の storage.backend）
+        #[arg(long, value_enum)]
+        from: Option<StorageBackend>,
+
+        /// data.json の代わりに使うJSONファイル（json が移行元・移行先の場合）
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+
+        /// 移行先にすでにデータがあっても上書きする
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// シェル補完スクリプトを出力
+    Completion {
+        /// 対象のシェル
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// 位置引数のタグ（`+` で始まるもののみ、タスク名の続きと区別するため）
+fn parse_tag_arg(s: &str) -> Result<String, String> {
+    if !s.starts_with('+') {
+        return Err(format!("タグは + で始めてください（タスク名に空白を含める場合は引用符で囲んでください）: {}", s));
+    }
+    data::parse_tag(s).map_err(|e| e.to_string())
+}
+
+/// `--tag` で指定したタグ（`+` は省略可）
+fn parse_tag_option(s: &str) -> Result<String, String> {
+    data::parse_tag(s).map_err(|e| e.to_string())
+}
+
+/// import で読み込めるファイルの形式
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// iCalendar（.ics）
+    #[default]
+    Ics,
+}
+
+/// 補完スクリプトを生成できるシェル
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl Shell {
+    /// シェルの名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::Powershell => "powershell",
+        }
+    }
 }
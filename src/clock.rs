@@ -0,0 +1,61 @@
+// 現在時刻の取得を抽象化する時計
+
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use std::sync::Mutex;
+
+/// 現在時刻を提供するトレイト
+/// TrackerとDataStoreは構築時に受け取った時計から現在時刻を取得する
+pub trait Clock: Send + Sync {
+    /// 現在時刻
+    fn now(&self) -> DateTime<Local>;
+
+    /// 今日の日付
+    fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+}
+
+/// システムの時計（デフォルト）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// 手動で操作できる時計（テストや過去データの入力用）
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Local>>,
+}
+
+impl FixedClock {
+    /// 指定時刻で止まった時計を作成
+    pub fn new(now: DateTime<Local>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    /// 時刻を設定
+    pub fn set(&self, now: DateTime<Local>) {
+        *self.lock() = now;
+    }
+
+    /// 時刻を進める
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.lock();
+        *now += duration;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DateTime<Local>> {
+        // 他スレッドのパニックで汚染されていても時刻自体は有効なので取り出す
+        self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Local> {
+        *self.lock()
+    }
+}
@@ -1,3 +1,79 @@
 // タブ補完機能
 
-// 後でTDDで実装
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::PathBuf;
+use crate::cli::Shell;
+use crate::config::{self, Config};
+use crate::data::TimeEntry;
+use crate::storage::{self, Storage};
+
+/// 補完スクリプトから呼び出されるときに設定される環境変数
+pub const COMPLETE_ENV_VAR: &str = "COMPLETE";
+
+/// 補完スクリプトに埋め込むコマンド名
+const BIN_NAME: &str = "time-checker";
+
+/// 過去に記録したタスク名を重複なしで取得（新しく使ったものから順）
+pub fn task_suggestions(entries: &[TimeEntry]) -> Vec<String> {
+    let mut sorted: Vec<&TimeEntry> = entries.iter().collect();
+    sorted.sort_by_key(|e| Reverse(e.start));
+
+    let mut seen = HashSet::new();
+    sorted
+        .into_iter()
+        .filter(|e| seen.insert(e.task.as_str()))
+        .map(|e| e.task.clone())
+        .collect()
+}
+
+/// 補完中のコマンドラインから `--data-dir` の値を取り出す（複数あれば最後のもの）
+pub fn data_dir_arg<I: IntoIterator<Item = OsString>>(args: I) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    let mut dir = None;
+    while let Some(arg) = args.next() {
+        if arg == "--data-dir" {
+            dir = args.next();
+        } else if let Some(value) = arg.to_str().and_then(|a| a.strip_prefix("--data-dir=")) {
+            dir = Some(value.into());
+        }
+    }
+    dir.filter(|d| !d.is_empty()).map(PathBuf::from)
+}
+
+/// `start <task>` の補完候補をデータファイルから生成
+/// 補完中にエラーを表示するとシェルの表示が崩れるため、失敗時は候補なしとする
+pub fn task_candidates() -> Vec<CompletionCandidate> {
+    let Ok(config) = Config::load() else {
+        return Vec::new();
+    };
+    let flag = data_dir_arg(std::env::args_os());
+    let Some(location) = config::default_location(flag, &config) else {
+        return Vec::new();
+    };
+
+    let entries = storage::open(config.storage.backend, &location)
+        .and_then(|storage| storage.load())
+        .unwrap_or_default();
+
+    task_suggestions(&entries)
+        .into_iter()
+        .enumerate()
+        .map(|(i, task)| CompletionCandidate::new(task).display_order(Some(i)))
+        .collect()
+}
+
+/// 指定したシェル用の補完スクリプトを書き出す
+/// 生成されるスクリプトは補完のたびに time-checker を呼び出すため、タスク名は常に最新になる
+pub fn generate(shell: Shell, buf: &mut dyn Write) -> std::io::Result<()> {
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(shell.name())
+        .ok_or_else(|| std::io::Error::other(format!("未対応のシェルです: {}", shell.name())))?;
+
+    completer.write_registration(COMPLETE_ENV_VAR, BIN_NAME, BIN_NAME, BIN_NAME, buf)
+}
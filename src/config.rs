@@ -0,0 +1,295 @@
+// 設定ファイル（config.toml）とデータの保存場所の解決
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::TimeCheckerError;
+use crate::report::{HourFormat, MarkdownStyle};
+
+/// データディレクトリを指定する環境変数
+pub const DATA_DIR_ENV: &str = "TIME_CHECKER_DATA_DIR";
+
+/// 設定ファイルのパスを指定する環境変数
+pub const CONFIG_FILE_ENV: &str = "TIME_CHECKER_CONFIG";
+
+/// データファイルの名前
+pub const DATA_FILE_NAME: &str = "data.json";
+
+/// SQLiteの保存先を使う場合のデータベースファイルの名前
+pub const DATABASE_FILE_NAME: &str = "data.db";
+
+/// 設定ファイルの名前
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// アプリケーション用ディレクトリの名前
+const APP_DIR_NAME: &str = "time-checker";
+
+/// config.toml の内容
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// データの保存に関する設定
+    pub storage: StorageConfig,
+
+    /// 集計のフィルタに関する設定
+    pub filters: FilterConfig,
+
+    /// Markdownのレポートに関する設定
+    pub report: ReportConfig,
+}
+
+/// `[storage]` セクション
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// データディレクトリ（`~` はホームディレクトリに展開される）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<PathBuf>,
+
+    /// 保存形式（`json`、`sqlite` または `journal`）
+    pub backend: StorageBackend,
+}
+
+/// データの保存形式
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// data.json（デフォルト）
+    #[default]
+    Json,
+    /// data.db（`sqlite` 機能を有効にしてビルドした場合のみ）
+    Sqlite,
+    /// journal.jsonl と snapshot.json（変更をイベントとして追記する）
+    Journal,
+}
+
+impl StorageBackend {
+    /// 設定ファイルでの名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageBackend::Json => "json",
+            StorageBackend::Sqlite => "sqlite",
+            StorageBackend::Journal => "journal",
+        }
+    }
+}
+
+/// `[filters]` セクション
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct FilterConfig {
+    /// 集計から除外するタスクのパターン（完全一致、`休憩*` で前方一致、`/.../` で正規表現）
+    pub exclude_tasks: Vec<String>,
+}
+
+/// `[report]` セクション
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ReportConfig {
+    /// タスクの並べ方（`table` または `list`）
+    pub style: MarkdownStyle,
+
+    /// 各タスクにエントリのメモを含めるかどうか
+    pub notes: bool,
+
+    /// 見出しの文言（`{period}` は期間の名前、`{range}` は日付範囲に置き換える）
+    pub heading: String,
+
+    /// 見出しのレベル（1〜6、`##` なら 2）
+    pub heading_level: u8,
+
+    /// 作業時間の書き方（`japanese`: 1時間30分、`decimal`: 1.50h、`clock`: 1:30）
+    pub hours: HourFormat,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            style: MarkdownStyle::default(),
+            notes: false,
+            heading: "{period}の作業時間 ({range})".to_string(),
+            heading_level: 2,
+            hours: HourFormat::default(),
+        }
+    }
+}
+
+impl Config {
+    /// デフォルトの場所から設定ファイルを読み込む（ファイルがなければデフォルト値）
+    pub fn load() -> Result<Self, TimeCheckerError> {
+        match config_file_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// 指定したパスから設定ファイルを読み込む（ファイルがなければデフォルト値）
+    pub fn load_from(path: &Path) -> Result<Self, TimeCheckerError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).map_err(|e| {
+            TimeCheckerError::ConfigError(format!("{} の読み込みに失敗: {}", path.display(), e))
+        })?;
+
+        toml::from_str(&content)
+            .map_err(|e| TimeCheckerError::ConfigError(format!("{}: {}", path.display(), e)))
+    }
+
+    /// TOML文字列から設定をパース
+    pub fn parse(content: &str) -> Result<Self, TimeCheckerError> {
+        toml::from_str(content).map_err(|e| TimeCheckerError::ConfigError(e.to_string()))
+    }
+}
+
+/// データディレクトリがどこから決まったか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataDirSource {
+    /// コマンドラインの `--data-dir`
+    Flag,
+    /// 環境変数 `TIME_CHECKER_DATA_DIR`
+    Env,
+    /// config.toml の `storage.data_dir`
+    Config,
+    /// XDGなどのプラットフォーム標準の場所
+    Default,
+}
+
+/// 解決済みのデータの保存場所
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataLocation {
+    /// データディレクトリ
+    pub dir: PathBuf,
+
+    /// どこから決まったか
+    pub source: DataDirSource,
+}
+
+impl DataLocation {
+    /// データファイルのパス
+    pub fn data_file(&self) -> PathBuf {
+        self.dir.join(DATA_FILE_NAME)
+    }
+
+    /// SQLiteのデータベースファイルのパス
+    pub fn database_file(&self) -> PathBuf {
+        self.dir.join(DATABASE_FILE_NAME)
+    }
+
+    /// 保存形式ごとのデータファイルのパス（ジャーナルはジャーナルファイル）
+    pub fn path_for(&self, backend: StorageBackend) -> PathBuf {
+        match backend {
+            StorageBackend::Json => self.data_file(),
+            StorageBackend::Sqlite => self.database_file(),
+            StorageBackend::Journal => self.dir.join(crate::journal::JOURNAL_FILE_NAME),
+        }
+    }
+}
+
+/// データディレクトリを決定する
+/// 優先順位: コマンドライン > 環境変数 > 設定ファイル > プラットフォーム標準の場所
+pub fn resolve_data_dir(
+    flag: Option<PathBuf>,
+    env: Option<PathBuf>,
+    config: &Config,
+) -> Result<DataLocation, TimeCheckerError> {
+    let candidates = [
+        (flag, DataDirSource::Flag),
+        (env.filter(|p| !p.as_os_str().is_empty()), DataDirSource::Env),
+        (config.storage.data_dir.clone(), DataDirSource::Config),
+    ];
+
+    for (dir, source) in candidates {
+        if let Some(dir) = dir {
+            return Ok(DataLocation { dir: expand_tilde(&dir), source });
+        }
+    }
+
+    let dir = default_data_dir().ok_or_else(|| {
+        TimeCheckerError::ConfigError(format!(
+            "データディレクトリを決定できません。--data-dir か環境変数 {} で指定してください",
+            DATA_DIR_ENV
+        ))
+    })?;
+
+    Ok(DataLocation { dir, source: DataDirSource::Default })
+}
+
+/// 引数を解析する前（タブ補完など）にデータの保存場所を決定
+/// `flag` はコマンドラインから取り出した `--data-dir` の値
+pub fn default_location(flag: Option<PathBuf>, config: &Config) -> Option<DataLocation> {
+    let env = std::env::var_os(DATA_DIR_ENV).map(PathBuf::from);
+    resolve_data_dir(flag, env, config).ok()
+}
+
+/// 設定ファイルのパス（環境変数 > プラットフォーム標準の設定ディレクトリ）
+pub fn config_file_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(CONFIG_FILE_ENV).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    dirs::config_dir().map(|dir| dir.join(APP_DIR_NAME).join(CONFIG_FILE_NAME))
+}
+
+/// プラットフォーム標準のデータディレクトリ
+/// Linux: ~/.local/share/time-checker, macOS: ~/Library/Application Support/time-checker
+pub fn default_data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_DIR_NAME))
+}
+
+/// 旧バージョンのデータディレクトリ（~/.time-checker）
+pub fn legacy_data_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".time-checker"))
+}
+
+/// 旧データディレクトリの中身を新しい場所へ移行する
+/// 移行先にすでにデータファイルがある場合は何もしない。移行した場合は true を返す
+pub fn migrate_legacy_data(legacy: &Path, target: &Path) -> Result<bool, TimeCheckerError> {
+    if legacy == target
+        || !legacy.join(DATA_FILE_NAME).exists()
+        || target.join(DATA_FILE_NAME).exists()
+    {
+        return Ok(false);
+    }
+
+    let migrate_error =
+        |e: std::io::Error| TimeCheckerError::ConfigError(format!("旧データディレクトリの移行に失敗: {}", e));
+
+    fs::create_dir_all(target).map_err(migrate_error)?;
+
+    for item in fs::read_dir(legacy).map_err(migrate_error)? {
+        let item = item.map_err(migrate_error)?;
+        if !item.file_type().map_err(migrate_error)?.is_file() {
+            continue;
+        }
+
+        let from = item.path();
+        let to = target.join(item.file_name());
+        if to.exists() {
+            continue;
+        }
+
+        // 別のファイルシステムへの移動は rename できないのでコピーしてから削除する
+        if fs::rename(&from, &to).is_err() {
+            fs::copy(&from, &to).map_err(migrate_error)?;
+            fs::remove_file(&from).map_err(migrate_error)?;
+        }
+    }
+
+    // 空になった旧ディレクトリは削除する（残っていても問題はない）
+    let _ = fs::remove_dir(legacy);
+
+    Ok(true)
+}
+
+/// 先頭の `~` をホームディレクトリに展開
+fn expand_tilde(path: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest);
+    }
+    path.to_path_buf()
+}
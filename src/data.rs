@@ -1,13 +1,28 @@
 // データ構造とDataStoreの実装
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Duration, Local, NaiveDate};
 use serde::{Deserialize, Serialize};
-use std::fs;
+use serde_json::Value;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::Write;
+use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use crate::clock::{Clock, SystemClock};
 use crate::error::TimeCheckerError;
+use crate::lock::{DataLock, DEFAULT_LOCK_TIMEOUT};
+use crate::storage::Storage;
+use crate::period::DateRange;
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
 pub struct TimeEntry {
+    /// エントリの識別子（1から始まる連番、0は未採番）
+    /// IDのない古いファイルは読み込み時に補完される
+    #[serde(default)]
+    pub id: u64,
+
     /// タスク名
     pub task: String,
 
@@ -20,17 +35,190 @@ pub struct TimeEntry {
     /// 備考・メモ（オプション）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
+
+    /// `pause` で一時停止中のエントリかどうか（`resume` で同じタスクを再開できる）
+    /// 再開・別タスクの開始・停止で false に戻る
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub paused: bool,
+
+    /// `resume` で再開した場合の、一時停止したエントリのID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resumed_from: Option<u64>,
+
+    /// タグ（`+` を除いた名前、作業の系統ごとの集計に使う）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl TimeEntry {
+    /// 指定したタグのいずれかを持っているかどうか（タグの指定がなければ常に true）
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+    }
+
+    /// 指定した時間帯 [from, to) と重なっているかどうか
+    /// 進行中のエントリは終わりがないものとして扱う
+    pub fn overlaps(&self, from: DateTime<Local>, to: DateTime<Local>) -> bool {
+        self.start < to && self.end.is_none_or(|end| end > from)
+    }
+
+    /// 他のエントリと時間が重なっているかどうか
+    /// 進行中のエントリは終わりがないものとして扱い、端点が接しているだけなら重ならない
+    pub fn conflicts_with(&self, other: &TimeEntry) -> bool {
+        let self_before_other_ends = other.end.is_none_or(|end| self.start < end);
+        let other_before_self_ends = self.end.is_none_or(|end| other.start < end);
+
+        self_before_other_ends && other_before_self_ends
+    }
+
+    /// 指定した時間帯 [from, to) に収まる部分の作業時間
+    /// 進行中のエントリは `now` までとみなす
+    pub fn duration_within(&self, from: DateTime<Local>, to: DateTime<Local>, now: DateTime<Local>) -> Duration {
+        let start = self.start.max(from);
+        let end = self.end.unwrap_or(now).min(to);
+
+        if end > start {
+            end.signed_duration_since(start)
+        } else {
+            Duration::zero()
+        }
+    }
+
+    /// 日付の境界（0時）で分割した日ごとの作業時間
+    /// 進行中のエントリは `now` までとみなす
+    pub fn split_by_day(&self, now: DateTime<Local>) -> Vec<(NaiveDate, Duration)> {
+        let end = self.end.unwrap_or(now);
+        if end <= self.start {
+            return Vec::new();
+        }
+
+        DateRange::new(self.start.date_naive(), end.date_naive())
+            .days()
+            .filter_map(|day| {
+                let (from, to) = DateRange::single(day).bounds();
+                let duration = self.duration_within(from, to, now);
+                (duration > Duration::zero()).then_some((day, duration))
+            })
+            .collect()
+    }
+}
+
+/// タグの指定（`+backend` または `backend`）をタグ名にする
+/// 空のタグや、空白・カンマを含むタグはエラー
+pub fn parse_tag(s: &str) -> Result<String, TimeCheckerError> {
+    let tag = s.strip_prefix('+').unwrap_or(s);
+
+    if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',' || c == '+') {
+        return Err(TimeCheckerError::InvalidTag(s.to_string()));
+    }
+    Ok(tag.to_string())
+}
+
+/// タグの重複を取り除く（最初に現れた順序を保つ）
+pub fn dedup_tags(tags: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        if !unique.contains(&tag) {
+            unique.push(tag);
+        }
+    }
+    unique
+}
+
+/// エントリの中で最大のID + 1（削除済みのエントリのIDは考慮しない）
+pub fn next_id(entries: &[TimeEntry]) -> u64 {
+    entries.iter().map(|e| e.id).max().unwrap_or(0) + 1
+}
+
+/// 保存されているエントリの一覧と、次に採番するID
+/// 削除したエントリのIDを新しいエントリに使い回さないよう、これまでに採番したIDの次の値を一緒に保存する
+/// `Vec<TimeEntry>` として扱える
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Entries {
+    entries: Vec<TimeEntry>,
+    next_id: u64,
+}
+
+impl Entries {
+    /// 保存されていた次のIDとともに作成（不明な場合は0、エントリの最大のID + 1 より小さくはならない）
+    pub fn new(entries: Vec<TimeEntry>, next_id: u64) -> Self {
+        let next_id = next_id.max(self::next_id(&entries));
+        Self { entries, next_id }
+    }
+
+    /// 未使用の次のID（削除済みのエントリのIDは使わない）
+    pub fn next_id(&self) -> u64 {
+        self.next_id.max(next_id(&self.entries))
+    }
+
+    /// IDが未採番（0）のエントリにファイル内の順序で連番を割り当てる
+    /// 既存のIDは変更しないため、同じファイルからは常に同じIDが得られる
+    pub fn assign_ids(&mut self) {
+        let first = self.next_id();
+
+        for (id, entry) in (first..).zip(self.entries.iter_mut().filter(|e| e.id == 0)) {
+            entry.id = id;
+        }
+        self.next_id = self.next_id();
+    }
+
+    /// エントリの一覧を取り出す
+    pub fn into_vec(self) -> Vec<TimeEntry> {
+        self.entries
+    }
+}
+
+impl From<Vec<TimeEntry>> for Entries {
+    fn from(entries: Vec<TimeEntry>) -> Self {
+        Self::new(entries, 0)
+    }
+}
+
+impl Deref for Entries {
+    type Target = Vec<TimeEntry>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+impl DerefMut for Entries {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.entries
+    }
 }
 
 /// データの永続化を担当する構造体
 pub struct DataStore {
     data_file: PathBuf,
+    clock: Arc<dyn Clock>,
+    lock_timeout: StdDuration,
 }
 
 impl DataStore {
-    /// 新しいDataStoreインスタンスを作成
+    /// 新しいDataStoreインスタンスを作成（システムの時計を使用）
     pub fn new(data_file: PathBuf) -> Self {
-        Self { data_file }
+        Self::with_clock(data_file, Arc::new(SystemClock))
+    }
+
+    /// 時計を指定してDataStoreインスタンスを作成
+    pub fn with_clock(data_file: PathBuf, clock: Arc<dyn Clock>) -> Self {
+        Self { data_file, clock, lock_timeout: DEFAULT_LOCK_TIMEOUT }
+    }
+
+    /// ロックが取得できるまで待つ時間を設定
+    pub fn with_lock_timeout(mut self, timeout: StdDuration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    /// 時計への参照を取得
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
     }
 
     /// データファイルのパスを取得
@@ -38,8 +226,30 @@ impl DataStore {
         &self.data_file
     }
 
-    /// エントリをファイルに保存
+    /// 直前に保存されていたデータのバックアップファイルのパス（`data.json.bak`）
+    pub fn backup_file(&self) -> PathBuf {
+        with_suffix(&self.data_file, ".bak")
+    }
+
+    /// ロックファイルのパス（`data.json.lock`）
+    pub fn lock_file(&self) -> PathBuf {
+        with_suffix(&self.data_file, ".lock")
+    }
+
+    /// データファイルの排他ロックを取得する（ロックはガードのドロップ時に解放される）
+    pub fn lock(&self) -> Result<DataLock, TimeCheckerError> {
+        DataLock::acquire(&self.lock_file(), self.lock_timeout)
+    }
+
+    /// エントリをファイルに保存（次に採番するIDはエントリの最大のID + 1）
     pub fn save(&self, entries: &[TimeEntry]) -> Result<(), TimeCheckerError> {
+        self.save_entries(&Entries::from(entries.to_vec()))
+    }
+
+    /// エントリを次に採番するIDとともにファイルに保存
+    /// 一時ファイルに書き込んでから置き換えるため、途中で中断されても元のファイルは壊れない
+    /// 置き換える前のファイルは `data.json.bak` として残す
+    pub fn save_entries(&self, entries: &Entries) -> Result<(), TimeCheckerError> {
         // 親ディレクトリが存在しない場合は作成
         if let Some(parent) = self.data_file.parent() {
             fs::create_dir_all(parent).map_err(|e| {
@@ -47,12 +257,21 @@ impl DataStore {
             })?;
         }
 
-        // JSONにシリアライズして保存
-        let json = serde_json::to_string_pretty(entries).map_err(|e| {
-            TimeCheckerError::DataSaveError(format!("シリアライズに失敗: {}", e))
-        })?;
+        // 未採番のエントリがあればIDを割り当ててから保存
+        let mut entries = entries.clone();
+        entries.assign_ids();
+
+        // バージョン付きの形式にシリアライズして保存
+        let json = serialize_data(&entries)?;
+
+        // 直前の正常なファイルをバックアップしてから置き換える
+        if fs::metadata(&self.data_file).is_ok_and(|m| m.len() > 0) {
+            fs::copy(&self.data_file, self.backup_file()).map_err(|e| {
+                TimeCheckerError::DataSaveError(format!("バックアップの作成に失敗: {}", e))
+            })?;
+        }
 
-        fs::write(&self.data_file, json).map_err(|e| {
+        write_atomic(&self.data_file, json.as_bytes()).map_err(|e| {
             TimeCheckerError::DataSaveError(format!("ファイルの書き込みに失敗: {}", e))
         })?;
 
@@ -61,9 +280,14 @@ impl DataStore {
 
     /// ファイルからエントリを読み込み
     pub fn load(&self) -> Result<Vec<TimeEntry>, TimeCheckerError> {
-        // ファイルが存在しない場合は空のベクタを返す
+        Ok(self.load_entries()?.into_vec())
+    }
+
+    /// ファイルからエントリを次に採番するIDとともに読み込み
+    pub fn load_entries(&self) -> Result<Entries, TimeCheckerError> {
+        // ファイルが存在しない場合は空の一覧を返す
         if !self.data_file.exists() {
-            return Ok(Vec::new());
+            return Ok(Entries::default());
         }
 
         // ファイルを読み込み
@@ -71,36 +295,204 @@ impl DataStore {
             TimeCheckerError::DataLoadError(format!("ファイルの読み込みに失敗: {}", e))
         })?;
 
-        // 空のファイルの場合は空のベクタを返す
+        // 空のファイルの場合は空の一覧を返す
         if content.trim().is_empty() {
-            return Ok(Vec::new());
+            return Ok(Entries::default());
         }
 
-        // JSONからデシリアライズ
-        let entries: Vec<TimeEntry> = serde_json::from_str(&content).map_err(|e| {
-            TimeCheckerError::DataLoadError(format!("デシリアライズに失敗: {}", e))
+        // JSONからデシリアライズ（古い形式は現在の形式に変換される）
+        let backup = self.backup_file();
+        let mut entries = parse_data(&content).map_err(|e| match e {
+            TimeCheckerError::DataLoadError(msg) if backup.exists() => TimeCheckerError::DataLoadError(format!(
+                "{}（直前のデータが {} に残っています）",
+                msg,
+                backup.display()
+            )),
+            e => e,
         })?;
 
+        // IDが省略されたエントリにはIDを補完
+        entries.assign_ids();
+
         Ok(entries)
     }
 
-    /// 現在進行中のタスクを取得（end が None のもの）
-    pub fn get_current_task(&self) -> Result<Option<TimeEntry>, TimeCheckerError> {
-        let entries = self.load()?;
+    /// 今日のエントリを取得（日付をまたいで今日にかかっているものも含む）
+    pub fn get_today_entries(&self) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        let today = self.clock.today();
+        self.get_entries_in_range(DateRange::single(today))
+    }
+}
+
+impl Storage for DataStore {
+    fn load_entries(&self) -> Result<Entries, TimeCheckerError> {
+        DataStore::load_entries(self)
+    }
 
-        // 最後の end が None のエントリを探す
-        Ok(entries.iter().rev().find(|e| e.end.is_none()).cloned())
+    fn save_entries(&self, entries: &Entries) -> Result<(), TimeCheckerError> {
+        DataStore::save_entries(self, entries)
     }
 
-    /// 今日のエントリを取得
-    pub fn get_today_entries(&self) -> Result<Vec<TimeEntry>, TimeCheckerError> {
-        let entries = self.load()?;
-        let today = Local::now().date_naive();
+    /// ロックを保持したまま 読み込み → 変更 → 保存 を行う
+    /// 何も変更しなかった場合は保存しない
+    fn update(
+        &self,
+        update: &mut dyn FnMut(&mut Entries) -> Result<(), TimeCheckerError>,
+    ) -> Result<(), TimeCheckerError> {
+        let _lock = self.lock()?;
+
+        let original = DataStore::load_entries(self)?;
+        let mut entries = original.clone();
+        update(&mut entries)?;
+
+        if entries != original {
+            DataStore::save_entries(self, &entries)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// データファイルの形式のバージョン
+/// - 1: エントリの配列のみ（IDなし）
+/// - 2: `{"version": 2, "entries": [...]}`、エントリにIDあり
+/// - 3: エントリに一時停止の情報（`paused`、`resumed_from`）あり
+/// - 4: エントリにタグ（`tags`）あり
+/// - 5: 次に採番するID（`next_id`）あり
+pub const DATA_VERSION: u32 = 5;
+
+/// 各バージョンのエントリの配列を1つ新しいバージョンへ変換する関数（添字は変換元のバージョン - 1）
+const MIGRATIONS: [fn(Value) -> Result<Value, TimeCheckerError>; DATA_VERSION as usize - 1] =
+    [migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4, migrate_v4_to_v5];
+
+/// 保存するファイルの形式
+#[derive(Serialize)]
+struct DataFile<'a> {
+    version: u32,
+    next_id: u64,
+    entries: &'a [TimeEntry],
+}
+
+/// エントリを現在のバージョンの形式でJSONにシリアライズする
+pub fn serialize_data(entries: &Entries) -> Result<String, TimeCheckerError> {
+    serde_json::to_string_pretty(&DataFile { version: DATA_VERSION, next_id: entries.next_id(), entries })
+        .map_err(|e| TimeCheckerError::DataSaveError(format!("シリアライズに失敗: {}", e)))
+}
 
-        // 今日の日付のエントリのみをフィルタ
-        Ok(entries
-            .into_iter()
-            .filter(|e| e.start.date_naive() == today)
-            .collect())
+/// データファイルの内容をパースする
+/// 古いバージョンの形式は順に変換してから読み込み、新しいバージョンの形式はエラーにする
+pub fn parse_data(content: &str) -> Result<Entries, TimeCheckerError> {
+    let invalid = |e: serde_json::Error| TimeCheckerError::DataLoadError(format!("デシリアライズに失敗: {}", e));
+    let document: Value = serde_json::from_str(content).map_err(invalid)?;
+
+    let (version, entries, next_id) = match document {
+        // バージョン1はエントリの配列のみ
+        Value::Array(_) => (1, Some(document), 0),
+        Value::Object(mut fields) => {
+            let version = fields
+                .get("version")
+                .and_then(Value::as_u64)
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| TimeCheckerError::DataLoadError("version がありません".to_string()))?;
+            let next_id = fields.get("next_id").and_then(Value::as_u64).unwrap_or(0);
+            (version, fields.remove("entries"), next_id)
+        }
+        _ => return Err(TimeCheckerError::DataLoadError("エントリの一覧がありません".to_string())),
+    };
+
+    if version == 0 {
+        return Err(TimeCheckerError::DataLoadError("version が不正です: 0".to_string()));
+    }
+    if version > DATA_VERSION {
+        return Err(TimeCheckerError::UnsupportedDataVersion(version));
+    }
+    // 空の一覧として読み込むと、次の保存でファイルの内容が失われてしまう
+    let mut entries =
+        entries.ok_or_else(|| TimeCheckerError::DataLoadError("エントリの一覧がありません".to_string()))?;
+
+    for migrate in &MIGRATIONS[version as usize - 1..] {
+        entries = migrate(entries)?;
+    }
+
+    let entries = serde_json::from_value(entries).map_err(invalid)?;
+    Ok(Entries::new(entries, next_id))
+}
+
+/// バージョン1 → 2: IDのないエントリにファイル内の順序で連番を振る
+fn migrate_v1_to_v2(mut entries: Value) -> Result<Value, TimeCheckerError> {
+    let list = entries
+        .as_array_mut()
+        .ok_or_else(|| TimeCheckerError::DataLoadError("エントリの一覧が配列ではありません".to_string()))?;
+
+    let id_of = |entry: &Value| entry.get("id").and_then(Value::as_u64).unwrap_or(0);
+    let mut next = list.iter().map(id_of).max().unwrap_or(0) + 1;
+
+    for entry in list.iter_mut() {
+        if id_of(entry) == 0
+            && let Some(fields) = entry.as_object_mut()
+        {
+            fields.insert("id".to_string(), Value::from(next));
+            next += 1;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// バージョン2 → 3: 一時停止の情報がないエントリは一時停止していないものとして読み込むので変換は不要
+/// （古い time-checker が一時停止の情報を落として上書きしないよう、バージョンだけを上げる）
+fn migrate_v2_to_v3(entries: Value) -> Result<Value, TimeCheckerError> {
+    Ok(entries)
+}
+
+/// バージョン3 → 4: タグのないエントリはタグなしとして読み込むので変換は不要
+fn migrate_v3_to_v4(entries: Value) -> Result<Value, TimeCheckerError> {
+    Ok(entries)
+}
+
+/// バージョン4 → 5: `next_id` のないファイルはエントリの最大のID + 1 から採番するので変換は不要
+/// （古い time-checker が `next_id` を落として上書きしないよう、バージョンだけを上げる）
+fn migrate_v4_to_v5(entries: Value) -> Result<Value, TimeCheckerError> {
+    Ok(entries)
+}
+
+/// ファイル名の末尾に文字列を付け足したパス（`data.json` → `data.json.bak` など）
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// 同じディレクトリの一時ファイルに書き込んで fsync し、rename で置き換える
+/// rename は同一ファイルシステム内でアトミックなので、読み手からは古い内容か新しい内容のどちらかしか見えない
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let temp = with_suffix(path, &format!(".tmp.{}", std::process::id()));
+
+    let result = (|| {
+        let mut file = File::create(&temp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+        return result;
+    }
+
+    sync_parent_dir(path);
+    Ok(())
+}
+
+/// rename をディスクに反映させるため親ディレクトリを fsync する（失敗しても書き込み自体は完了している）
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty())
+        && let Ok(dir) = File::open(parent)
+    {
+        let _ = dir.sync_all();
     }
 }
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) {}
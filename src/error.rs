@@ -7,6 +7,22 @@ pub enum TimeCheckerError {
     DataLoadError(String),
     DataSaveError(String),
     InvalidPeriod(String),
+    ConfigError(String),
+    InvalidPattern(String),
+    InvalidTime(String),
+    InvalidTimeRange(String),
+    EntryNotFound(u64),
+    OverlappingEntry(u64),
+    LockTimeout(String),
+    UnsupportedDataVersion(u32),
+    NotPaused,
+    NoRecentTask(usize),
+    InvalidSelection(String),
+    InvalidTag(String),
+    NothingToUndo,
+    NothingToRedo,
+    HistoryConflict(u64),
+    InvalidCalendar(String),
 }
 
 impl fmt::Display for TimeCheckerError {
@@ -16,6 +32,39 @@ impl fmt::Display for TimeCheckerError {
             TimeCheckerError::DataLoadError(msg) => write!(f, "データの読み込みに失敗しました: {}", msg),
             TimeCheckerError::DataSaveError(msg) => write!(f, "データの保存に失敗しました: {}", msg),
             TimeCheckerError::InvalidPeriod(period) => write!(f, "無効な期間指定です: {}", period),
+            TimeCheckerError::ConfigError(msg) => write!(f, "設定の読み込みに失敗しました: {}", msg),
+            TimeCheckerError::InvalidPattern(msg) => write!(f, "無効なタスクのパターンです: {}", msg),
+            TimeCheckerError::InvalidTime(time) => write!(f, "無効な時刻指定です: {}", time),
+            TimeCheckerError::InvalidTimeRange(msg) => write!(f, "無効な時間範囲です: {}", msg),
+            TimeCheckerError::EntryNotFound(id) => write!(f, "ID {} のエントリが見つかりません", id),
+            TimeCheckerError::OverlappingEntry(id) => write!(f, "ID {} のエントリと時間が重なっています", id),
+            TimeCheckerError::LockTimeout(path) => write!(
+                f,
+                "他のプロセスがデータを更新中です（{}）。しばらく待ってから再実行してください",
+                path
+            ),
+            TimeCheckerError::UnsupportedDataVersion(version) => write!(
+                f,
+                "データファイルの形式（バージョン {}）はこの time-checker では読み込めません（バージョン {} まで対応）。time-checker を更新してください",
+                version,
+                crate::data::DATA_VERSION
+            ),
+            TimeCheckerError::NotPaused => write!(f, "一時停止中のタスクがありません"),
+            TimeCheckerError::NoRecentTask(nth) => write!(f, "{}番目に最近のタスクがありません", nth),
+            TimeCheckerError::InvalidSelection(input) => write!(f, "無効な選択です: {}", input),
+            TimeCheckerError::InvalidTag(tag) => write!(
+                f,
+                "無効なタグです: {}（空白・カンマ・+ を含まない名前を指定してください）",
+                tag
+            ),
+            TimeCheckerError::NothingToUndo => write!(f, "取り消せる操作がありません"),
+            TimeCheckerError::NothingToRedo => write!(f, "やり直せる操作がありません"),
+            TimeCheckerError::HistoryConflict(id) => write!(
+                f,
+                "ID {} のエントリが操作の後に変更されているため、取り消し・やり直しできません",
+                id
+            ),
+            TimeCheckerError::InvalidCalendar(msg) => write!(f, "iCalendarの読み込みに失敗しました: {}", msg),
         }
     }
 }
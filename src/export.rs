@@ -0,0 +1,168 @@
+// CSV形式でのエクスポート（RFC 4180）
+// iCalendar形式は ical.rs
+
+use chrono::{DateTime, Local, NaiveDate};
+use clap::ValueEnum;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::time::Duration;
+use crate::data::TimeEntry;
+use crate::period::{self, DateRange};
+
+/// Excel で UTF-8 として開かせるための BOM
+pub const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// エクスポートする内容
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportKind {
+    /// エントリを1行ずつ
+    Entries,
+    /// 期間ごと・タスクごとの集計
+    Summary,
+}
+
+/// 出力の形式
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// CSV（RFC 4180）
+    #[default]
+    Csv,
+    /// iCalendar（entries のみ、終了済みのエントリを予定として出力）
+    Ics,
+}
+
+/// 集計の単位
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Granularity {
+    /// 日ごと
+    #[default]
+    Day,
+    /// 週ごと（月曜始まり）
+    Week,
+    /// 月ごと
+    Month,
+    /// 期間全体
+    Total,
+}
+
+impl Granularity {
+    /// 指定日を含む集計単位の範囲（`range` からはみ出す部分は切り詰める）
+    pub fn bucket(&self, date: NaiveDate, range: DateRange) -> DateRange {
+        let bucket = match self {
+            Granularity::Day => DateRange::single(date),
+            Granularity::Week => period::week_of(date),
+            Granularity::Month => period::month_of(date),
+            Granularity::Total => range,
+        };
+        DateRange::new(bucket.start.max(range.start), bucket.end.min(range.end))
+    }
+}
+
+/// 集計結果の1行
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryRow {
+    /// 集計した範囲
+    pub range: DateRange,
+
+    /// タスク名
+    pub task: String,
+
+    /// 作業時間
+    pub duration: Duration,
+}
+
+/// フィールドを CSV 用に引用する
+/// カンマ・ダブルクォート・改行を含む場合だけダブルクォートで囲み、中のダブルクォートは2つ重ねる
+pub fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// 1行分のフィールドを CRLF 区切りで書き出す
+pub fn write_record<W: Write + ?Sized, S: AsRef<str>>(out: &mut W, fields: &[S]) -> io::Result<()> {
+    let line: Vec<_> = fields.iter().map(|f| csv_field(f.as_ref())).collect();
+    write!(out, "{}\r\n", line.join(","))
+}
+
+/// エントリを1行ずつ書き出す（進行中のエントリは終了時刻を空にし、`now` までの時間を出す）
+pub fn write_entries_csv<W: Write + ?Sized>(
+    out: &mut W,
+    entries: &[TimeEntry],
+    now: DateTime<Local>,
+) -> io::Result<()> {
+    write_record(out, &["id", "task", "start", "end", "duration", "note", "tags"])?;
+
+    for entry in entries {
+        let duration = entry.end.unwrap_or(now).signed_duration_since(entry.start);
+        write_record(
+            out,
+            &[
+                entry.id.to_string(),
+                entry.task.clone(),
+                format_time(entry.start),
+                entry.end.map(format_time).unwrap_or_default(),
+                format_hms(duration.to_std().unwrap_or_default()),
+                entry.note.clone().unwrap_or_default(),
+                entry.tags.join(" "),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// 日ごとの集計を `per` の単位にまとめ直す（範囲の開始日・タスク名の順）
+pub fn aggregate(
+    daily: &BTreeMap<NaiveDate, HashMap<String, Duration>>,
+    range: DateRange,
+    per: Granularity,
+) -> Vec<SummaryRow> {
+    let mut buckets: BTreeMap<(NaiveDate, String), SummaryRow> = BTreeMap::new();
+
+    for (day, tasks) in daily.range(range.start..=range.end) {
+        let bucket = per.bucket(*day, range);
+        for (task, duration) in tasks {
+            buckets
+                .entry((bucket.start, task.clone()))
+                .or_insert_with(|| SummaryRow { range: bucket, task: task.clone(), duration: Duration::ZERO })
+                .duration += *duration;
+        }
+    }
+
+    buckets.into_values().collect()
+}
+
+/// 集計結果を書き出す（時間は H:MM:SS と小数の時間の両方）
+pub fn write_summary_csv<W: Write + ?Sized>(out: &mut W, rows: &[SummaryRow]) -> io::Result<()> {
+    write_record(out, &["from", "to", "task", "duration", "hours"])?;
+
+    for row in rows {
+        write_record(
+            out,
+            &[
+                row.range.start.format("%Y-%m-%d").to_string(),
+                row.range.end.format("%Y-%m-%d").to_string(),
+                row.task.clone(),
+                format_hms(row.duration),
+                format!("{:.2}", row.duration.as_secs_f64() / 3600.0),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// 日時を表計算ソフトで扱いやすい形式にする
+fn format_time(time: DateTime<Local>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 時間を H:MM:SS 形式にする
+fn format_hms(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
@@ -0,0 +1,142 @@
+// タスク名のパターンマッチング（集計からの除外などに使用）
+
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+use crate::error::TimeCheckerError;
+
+/// タスク名のパターン
+///
+/// - `昼休憩` : 完全一致
+/// - `休憩*` : 前方一致（末尾の `*`）
+/// - `/^MTG-\d+$/` : 正規表現（`/` で囲む）
+#[derive(Debug, Clone)]
+pub enum TaskPattern {
+    Exact(String),
+    Prefix(String),
+    Regex(Regex),
+}
+
+impl TaskPattern {
+    /// タスク名がパターンに一致するか
+    pub fn matches(&self, task: &str) -> bool {
+        match self {
+            TaskPattern::Exact(name) => task == name,
+            TaskPattern::Prefix(prefix) => task.starts_with(prefix.as_str()),
+            TaskPattern::Regex(re) => re.is_match(task),
+        }
+    }
+}
+
+impl FromStr for TaskPattern {
+    type Err = TimeCheckerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pattern = s.trim();
+
+        if pattern.is_empty() {
+            return Err(TimeCheckerError::InvalidPattern("空のパターンは指定できません".to_string()));
+        }
+
+        if pattern.len() >= 2 && pattern.starts_with('/') && pattern.ends_with('/') {
+            let body = &pattern[1..pattern.len() - 1];
+            return Regex::new(body)
+                .map(TaskPattern::Regex)
+                .map_err(|e| TimeCheckerError::InvalidPattern(format!("{}: {}", pattern, e)));
+        }
+
+        if let Some(prefix) = pattern.strip_suffix('*') {
+            return Ok(TaskPattern::Prefix(prefix.to_string()));
+        }
+
+        Ok(TaskPattern::Exact(pattern.to_string()))
+    }
+}
+
+impl fmt::Display for TaskPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TaskPattern::Exact(name) => write!(f, "{}", name),
+            TaskPattern::Prefix(prefix) => write!(f, "{}*", prefix),
+            TaskPattern::Regex(re) => write!(f, "/{}/", re.as_str()),
+        }
+    }
+}
+
+/// 複数のパターンのいずれかに一致するタスクを選ぶフィルタ
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    patterns: Vec<TaskPattern>,
+}
+
+impl TaskFilter {
+    /// 何にも一致しないフィルタ
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// パターン文字列のリストからフィルタを作成
+    pub fn parse<S: AsRef<str>>(patterns: &[S]) -> Result<Self, TimeCheckerError> {
+        let patterns = patterns
+            .iter()
+            .map(|p| p.as_ref().parse())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    /// コマンドラインの引数（それぞれカンマ区切り）からフィルタを作成
+    pub fn parse_args<S: AsRef<str>>(args: &[S]) -> Result<Self, TimeCheckerError> {
+        let patterns: Vec<String> = args.iter().flat_map(|arg| split_patterns(arg.as_ref())).collect();
+        Self::parse(&patterns)
+    }
+
+    /// タスク名がいずれかのパターンに一致するか
+    pub fn matches(&self, task: &str) -> bool {
+        self.patterns.iter().any(|p| p.matches(task))
+    }
+
+    /// パターンが1つもないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// パターンの一覧
+    pub fn patterns(&self) -> &[TaskPattern] {
+        &self.patterns
+    }
+}
+
+/// カンマ区切りのパターンを分割する
+/// 正規表現（`/` で始まるパターン）の中のカンマは区切りとみなさない（`/x{1,3}/` など）
+pub fn split_patterns(list: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut current = String::new();
+    let mut in_regex = false;
+    let mut escaped = false;
+
+    for c in list.chars() {
+        if in_regex {
+            current.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '/' => in_regex = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            ',' => patterns.push(std::mem::take(&mut current)),
+            '/' if current.trim().is_empty() => {
+                current.push(c);
+                in_regex = true;
+            }
+            _ => current.push(c),
+        }
+    }
+
+    patterns.push(current);
+    patterns
+}
@@ -0,0 +1,255 @@
+// 操作の履歴（undo / redo）
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration as StdDuration;
+use crate::data::{self, TimeEntry};
+use crate::error::TimeCheckerError;
+use crate::lock::{DataLock, DEFAULT_LOCK_TIMEOUT};
+
+/// 操作の履歴を保存するファイルの名前
+pub const UNDO_FILE_NAME: &str = "undo.json";
+
+/// 取り消せる操作の数のデフォルト
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// 記録する操作の種類
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationKind {
+    Start,
+    Stop,
+    Add,
+    Edit,
+    Delete,
+    Pause,
+    Resume,
+    Import,
+}
+
+impl OperationKind {
+    /// 表示用の名前
+    pub fn label(&self) -> &'static str {
+        match self {
+            OperationKind::Start => "開始",
+            OperationKind::Stop => "停止",
+            OperationKind::Add => "追加",
+            OperationKind::Edit => "編集",
+            OperationKind::Delete => "削除",
+            OperationKind::Pause => "一時停止",
+            OperationKind::Resume => "再開",
+            OperationKind::Import => "インポート",
+        }
+    }
+}
+
+/// 1つのエントリの変更前と変更後（追加なら変更前が、削除なら変更後が None）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EntryChange {
+    pub id: u64,
+    pub before: Option<TimeEntry>,
+    pub after: Option<TimeEntry>,
+}
+
+/// 記録された1回の操作
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Operation {
+    /// 操作の種類
+    pub kind: OperationKind,
+
+    /// 操作した時刻
+    pub at: DateTime<Local>,
+
+    /// 操作で変わったエントリ
+    pub changes: Vec<EntryChange>,
+}
+
+impl Operation {
+    /// 変更前と変更後のエントリから操作を作成
+    pub fn new(kind: OperationKind, at: DateTime<Local>, before: &[TimeEntry], after: &[TimeEntry]) -> Self {
+        Self { kind, at, changes: changes_between(before, after) }
+    }
+
+    /// 操作を取り消す（各エントリを変更前に戻す）
+    /// 操作の後にエントリが別の変更を受けている場合は何も変えずにエラーを返す
+    pub fn revert(&self, entries: &mut Vec<TimeEntry>) -> Result<(), TimeCheckerError> {
+        let steps: Vec<_> = self.changes.iter().rev().map(|c| (c.id, &c.after, &c.before)).collect();
+        apply_changes(entries, &steps)
+    }
+
+    /// 取り消した操作をやり直す（各エントリを変更後にする）
+    pub fn reapply(&self, entries: &mut Vec<TimeEntry>) -> Result<(), TimeCheckerError> {
+        let steps: Vec<_> = self.changes.iter().map(|c| (c.id, &c.before, &c.after)).collect();
+        apply_changes(entries, &steps)
+    }
+
+    /// 操作の中心となるエントリ（開始なら新しいエントリ、削除なら削除したエントリ）
+    fn subject(&self) -> Option<&TimeEntry> {
+        let change = match self.kind {
+            OperationKind::Start | OperationKind::Add | OperationKind::Resume => {
+                self.changes.iter().find(|c| c.before.is_none())
+            }
+            _ => self.changes.first(),
+        }?;
+        change.after.as_ref().or(change.before.as_ref())
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} の{}", self.at.format("%Y-%m-%d %H:%M"), self.kind.label())?;
+        if let Some(entry) = self.subject() {
+            write!(f, ": [{}] {}", entry.id, entry.task)?;
+        }
+        Ok(())
+    }
+}
+
+/// 変更前と変更後のエントリを比べて、変わったエントリを列挙する
+fn changes_between(before: &[TimeEntry], after: &[TimeEntry]) -> Vec<EntryChange> {
+    let old: HashMap<u64, &TimeEntry> = before.iter().map(|e| (e.id, e)).collect();
+    let new: HashMap<u64, &TimeEntry> = after.iter().map(|e| (e.id, e)).collect();
+
+    let deleted = before.iter().filter(|e| !new.contains_key(&e.id)).map(|e| EntryChange {
+        id: e.id,
+        before: Some(e.clone()),
+        after: None,
+    });
+
+    let changed = after
+        .iter()
+        .filter(|e| old.get(&e.id) != Some(e))
+        .map(|e| EntryChange {
+            id: e.id,
+            before: old.get(&e.id).map(|&old| old.clone()),
+            after: Some(e.clone()),
+        });
+
+    deleted.chain(changed).collect()
+}
+
+/// 各エントリが `expected` の状態であることを確かめてから `target` の状態にする
+fn apply_changes(
+    entries: &mut Vec<TimeEntry>,
+    steps: &[(u64, &Option<TimeEntry>, &Option<TimeEntry>)],
+) -> Result<(), TimeCheckerError> {
+    for (id, expected, _) in steps {
+        let current = entries.iter().find(|e| e.id == *id);
+        if current != expected.as_ref() {
+            return Err(TimeCheckerError::HistoryConflict(*id));
+        }
+    }
+
+    for (id, _, target) in steps {
+        let index = entries.iter().position(|e| e.id == *id);
+        match (index, target) {
+            (Some(index), Some(entry)) => entries[index] = entry.clone(),
+            (Some(index), None) => {
+                entries.remove(index);
+            }
+            (None, Some(entry)) => entries.push(entry.clone()),
+            (None, None) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// 履歴ファイルの内容
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HistoryStacks {
+    /// 取り消せる操作（最後が最新）
+    pub undo: Vec<Operation>,
+
+    /// やり直せる操作（最後が直前に取り消したもの）
+    pub redo: Vec<Operation>,
+}
+
+/// データファイルの隣に保存する操作の履歴
+pub struct OperationHistory {
+    path: PathBuf,
+    limit: usize,
+    lock_timeout: StdDuration,
+}
+
+impl OperationHistory {
+    /// 指定したファイルに履歴を保存する
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, limit: DEFAULT_HISTORY_LIMIT, lock_timeout: DEFAULT_LOCK_TIMEOUT }
+    }
+
+    /// 残しておく操作の数を設定
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// 履歴ファイルのパス
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 履歴を読み込む（ファイルがなければ空）
+    pub fn load(&self) -> Result<HistoryStacks, TimeCheckerError> {
+        if !self.path.exists() {
+            return Ok(HistoryStacks::default());
+        }
+
+        let content = fs::read_to_string(&self.path).map_err(|e| {
+            TimeCheckerError::DataLoadError(format!("{} の読み込みに失敗: {}", self.path.display(), e))
+        })?;
+        if content.trim().is_empty() {
+            return Ok(HistoryStacks::default());
+        }
+
+        serde_json::from_str(&content).map_err(|e| {
+            TimeCheckerError::DataLoadError(format!("{} のデシリアライズに失敗: {}", self.path.display(), e))
+        })
+    }
+
+    /// 新しい操作を記録する（やり直せる操作は破棄される）
+    pub fn record(&self, operation: Operation) -> Result<(), TimeCheckerError> {
+        if operation.changes.is_empty() {
+            return Ok(());
+        }
+
+        let limit = self.limit;
+        self.modify(|stacks| {
+            stacks.undo.push(operation);
+            stacks.redo.clear();
+
+            let excess = stacks.undo.len().saturating_sub(limit);
+            stacks.undo.drain(..excess);
+            Ok(())
+        })
+    }
+
+    /// ロックを保持したまま履歴を読み込み、変更して保存する
+    /// `update` がエラーを返した場合は保存しない
+    pub fn modify<T>(
+        &self,
+        update: impl FnOnce(&mut HistoryStacks) -> Result<T, TimeCheckerError>,
+    ) -> Result<T, TimeCheckerError> {
+        let _lock = DataLock::acquire(&self.path.with_extension("lock"), self.lock_timeout)?;
+
+        let mut stacks = self.load()?;
+        let result = update(&mut stacks)?;
+
+        let json = serde_json::to_string_pretty(&stacks)
+            .map_err(|e| TimeCheckerError::DataSaveError(format!("シリアライズに失敗: {}", e)))?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| TimeCheckerError::DataSaveError(format!("ディレクトリの作成に失敗: {}", e)))?;
+        }
+        data::write_atomic(&self.path, json.as_bytes()).map_err(|e| {
+            TimeCheckerError::DataSaveError(format!("{} の書き込みに失敗: {}", self.path.display(), e))
+        })?;
+
+        Ok(result)
+    }
+}
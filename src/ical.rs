@@ -0,0 +1,793 @@
+// iCalendar（RFC 5545）形式の読み書き
+
+use chrono::{DateTime, Days, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::SystemTime;
+use crate::data::{self, TimeEntry};
+use crate::error::TimeCheckerError;
+use crate::period::DateRange;
+use crate::recurrence::Recurrence;
+use crate::tracker::SkipReason;
+
+/// カレンダーを作成した製品の識別子
+pub const PRODUCT_ID: &str = concat!("-//time-checker//time-checker ", env!("CARGO_PKG_VERSION"), "//JA");
+
+/// UID のドメイン部分
+pub const UID_DOMAIN: &str = "time-checker";
+
+/// UID に含めるデータディレクトリの識別子を保存するファイルの名前
+pub const CALENDAR_ID_FILE_NAME: &str = "calendar-id";
+
+/// 1行の最大の長さ（バイト数、改行を除く）
+const MAX_LINE_OCTETS: usize = 75;
+
+/// カレンダーから読み込んだ予定
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    /// 予定の UID
+    pub uid: Option<String>,
+
+    /// 件名
+    pub summary: String,
+
+    /// 説明
+    pub description: Option<String>,
+
+    /// カテゴリ
+    pub categories: Vec<String>,
+
+    /// 開始時刻
+    pub start: DateTime<Local>,
+
+    /// 終了時刻
+    pub end: DateTime<Local>,
+}
+
+impl CalendarEvent {
+    /// 終了済みのエントリにする（件名がタスク名、説明がメモ）
+    /// カテゴリのうちタグとして使える名前と `tags` をタグにする
+    pub fn to_entry(&self, tags: &[String]) -> TimeEntry {
+        let tags = self
+            .categories
+            .iter()
+            .filter_map(|c| data::parse_tag(c).ok())
+            .chain(tags.iter().cloned())
+            .collect();
+
+        TimeEntry {
+            task: self.summary.clone(),
+            start: self.start,
+            end: Some(self.end),
+            note: self.description.clone(),
+            tags: data::dedup_tags(tags),
+            ..Default::default()
+        }
+    }
+}
+
+/// データディレクトリの識別子を取得する（なければ作成して `calendar-id` に保存する）
+/// 別のデータディレクトリからエクスポートした予定の UID が重ならないようにするため
+pub fn calendar_id(dir: &Path) -> Result<String, TimeCheckerError> {
+    let path = dir.join(CALENDAR_ID_FILE_NAME);
+    if let Ok(content) = fs::read_to_string(&path)
+        && !content.trim().is_empty()
+    {
+        return Ok(content.trim().to_string());
+    }
+
+    // 乱数の種を持つハッシュで、時刻とプロセスIDから推測しにくい値を作る
+    let id = format!("{:016x}", RandomState::new().hash_one((SystemTime::now(), std::process::id())));
+    let save_error = |e: io::Error| {
+        TimeCheckerError::DataSaveError(format!("{} の作成に失敗: {}", path.display(), e))
+    };
+    fs::create_dir_all(dir).map_err(save_error)?;
+    data::write_atomic(&path, id.as_bytes()).map_err(save_error)?;
+    Ok(id)
+}
+
+/// エントリの UID（同じデータディレクトリの同じエントリは、何度エクスポートしても同じ UID になる）
+/// エントリのIDは削除後も再利用されないので、別のエントリと UID が重なることはない
+pub fn entry_uid(entry: &TimeEntry, calendar_id: &str) -> String {
+    format!("entry-{}-{}@{}", calendar_id, entry.id, UID_DOMAIN)
+}
+
+/// 終了済みのエントリをそれぞれ VEVENT としたカレンダーを書き出す（進行中のエントリは含めない）
+/// 時刻はエントリのオフセットを反映したうえで UTC にする
+pub fn write_calendar<W: Write + ?Sized>(
+    out: &mut W,
+    entries: &[TimeEntry],
+    calendar_id: &str,
+    now: DateTime<Local>,
+) -> io::Result<()> {
+    write_line(out, "BEGIN:VCALENDAR")?;
+    write_line(out, "VERSION:2.0")?;
+    write_line(out, &format!("PRODID:{}", PRODUCT_ID))?;
+    write_line(out, "CALSCALE:GREGORIAN")?;
+    write_line(out, "METHOD:PUBLISH")?;
+
+    let stamp = format_utc(now);
+    for entry in entries {
+        let Some(end) = entry.end else {
+            continue;
+        };
+
+        write_line(out, "BEGIN:VEVENT")?;
+        write_line(out, &format!("UID:{}", entry_uid(entry, calendar_id)))?;
+        write_line(out, &format!("DTSTAMP:{}", stamp))?;
+        write_line(out, &format!("DTSTART:{}", format_utc(entry.start)))?;
+        write_line(out, &format!("DTEND:{}", format_utc(end)))?;
+        write_line(out, &format!("SUMMARY:{}", escape_text(&entry.task)))?;
+        if let Some(ref note) = entry.note {
+            write_line(out, &format!("DESCRIPTION:{}", escape_text(note)))?;
+        }
+        if !entry.tags.is_empty() {
+            let tags: Vec<_> = entry.tags.iter().map(|tag| escape_text(tag)).collect();
+            write_line(out, &format!("CATEGORIES:{}", tags.join(",")))?;
+        }
+        // 予定ではなく実績なので空き時間として扱わせる
+        write_line(out, "TRANSP:TRANSPARENT")?;
+        write_line(out, "END:VEVENT")?;
+    }
+
+    write_line(out, "END:VCALENDAR")
+}
+
+/// カレンダーから読み込んだ期間内の予定
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedCalendar {
+    /// 取り込める予定（開始時刻順）
+    pub events: Vec<CalendarEvent>,
+
+    /// 時刻を正しく求められないため取り込まない予定（開始時刻順）
+    pub skipped: Vec<SkippedEvent>,
+}
+
+/// 時刻を正しく求められないため取り込まない予定
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedEvent {
+    /// 予定（定義のないタイムゾーンの時刻は端末のタイムゾーンとみなす）
+    pub event: CalendarEvent,
+
+    /// 取り込まない理由
+    pub reason: SkipReason,
+}
+
+/// カレンダーから開始日が `range` 内の予定（VEVENT）を読み込む
+/// 終日の予定、キャンセルされた予定、件名や終了時刻のない予定は含めない
+/// `TZID` 付きの時刻は VTIMEZONE の定義で変換し、繰り返しの予定（`RRULE`）は期間内の回に展開する
+/// 定義のないタイムゾーンや対応していない繰り返しの規則の予定は `skipped` に入れる
+pub fn parse_calendar(content: &str, range: &DateRange) -> Result<ParsedCalendar, TimeCheckerError> {
+    let root = Component::parse(content);
+    let calendars: Vec<_> = root.components("VCALENDAR").collect();
+    if calendars.is_empty() {
+        return Err(TimeCheckerError::InvalidCalendar("BEGIN:VCALENDAR がありません".to_string()));
+    }
+
+    let mut parsed = ParsedCalendar::default();
+    for calendar in calendars {
+        let zones = TimeZones::from_calendar(calendar);
+
+        // RECURRENCE-ID のある予定で置き換えられた（または中止された）回
+        let mut overridden = Vec::new();
+        for event in calendar.components("VEVENT") {
+            if let (Some(uid), Some(id)) = (event.property("UID"), event.property("RECURRENCE-ID"))
+                && !is_date_only(id)
+            {
+                let at = zones.resolve(&TimeValue::parse(id)?).ok_or_else(|| invalid_time(&id.value))?;
+                overridden.push((uid.value.as_str(), at));
+            }
+        }
+
+        for event in calendar.components("VEVENT") {
+            expand_event(event, &zones, &overridden, range, &mut parsed)?;
+        }
+    }
+
+    parsed.events.sort_by_key(|e| e.start);
+    parsed.skipped.sort_by_key(|s| s.event.start);
+    Ok(parsed)
+}
+
+/// コンポーネント（`BEGIN:VEVENT` 〜 `END:VEVENT` など）
+#[derive(Debug, Default)]
+struct Component {
+    /// 大文字にした名前
+    name: String,
+
+    /// プロパティ
+    properties: Vec<Property>,
+
+    /// 中のコンポーネント
+    children: Vec<Component>,
+}
+
+impl Component {
+    /// 最上位のコンポーネントを子に持つ、名前のないコンポーネントとしてパースする
+    /// 閉じられていないコンポーネントはファイルの終わりで閉じる
+    fn parse(content: &str) -> Self {
+        fn close(stack: &mut Vec<Component>) {
+            if stack.len() > 1
+                && let Some(component) = stack.pop()
+                && let Some(parent) = stack.last_mut()
+            {
+                parent.children.push(component);
+            }
+        }
+
+        let mut stack = vec![Component::default()];
+        for line in unfold_lines(content) {
+            let Some(property) = Property::parse(&line) else {
+                continue;
+            };
+
+            match property.name.as_str() {
+                "BEGIN" => stack.push(Component { name: property.value.to_ascii_uppercase(), ..Default::default() }),
+                "END" => close(&mut stack),
+                _ => {
+                    if let Some(component) = stack.last_mut() {
+                        component.properties.push(property);
+                    }
+                }
+            }
+        }
+
+        while stack.len() > 1 {
+            close(&mut stack);
+        }
+        stack.pop().unwrap_or_default()
+    }
+
+    /// 指定した名前の子のコンポーネント
+    fn components<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Component> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// 指定した名前の最初のプロパティ
+    fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    /// 指定した名前のすべてのプロパティ
+    fn properties_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Property> {
+        self.properties.iter().filter(move |p| p.name == name)
+    }
+}
+
+/// 1行分のプロパティ（`NAME;PARAM=VALUE:値`）
+#[derive(Debug)]
+struct Property {
+    /// 大文字にした名前
+    name: String,
+
+    /// 大文字にしたパラメータ名と値
+    params: Vec<(String, String)>,
+
+    /// 値（エスケープは解除していない）
+    value: String,
+}
+
+impl Property {
+    /// 1行をパースする（`:` のない行は無視する）
+    fn parse(line: &str) -> Option<Self> {
+        // 引用符の中の `:` と `;` は区切りとみなさない
+        let mut in_quotes = false;
+        let mut separators = Vec::new();
+        let mut colon = None;
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                ';' if !in_quotes => separators.push(i),
+                ':' if !in_quotes => {
+                    colon = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let colon = colon?;
+        let head = &line[..colon];
+        let name_end = separators.first().copied().unwrap_or(colon);
+        let params = separators
+            .iter()
+            .zip(separators.iter().skip(1).copied().chain([colon]))
+            .filter_map(|(&from, to)| {
+                let (key, value) = line[from + 1..to].split_once('=')?;
+                Some((key.to_ascii_uppercase(), value.trim_matches('"').to_string()))
+            })
+            .collect();
+
+        Some(Self {
+            name: head[..name_end].to_ascii_uppercase(),
+            params,
+            value: line[colon + 1..].to_string(),
+        })
+    }
+
+    /// パラメータの値
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+/// VEVENT を期間内の回の予定にする
+/// `overridden` は RECURRENCE-ID のある予定で置き換えられた回（UID と元の開始時刻）
+fn expand_event(
+    component: &Component,
+    zones: &TimeZones,
+    overridden: &[(&str, DateTime<Local>)],
+    range: &DateRange,
+    parsed: &mut ParsedCalendar,
+) -> Result<(), TimeCheckerError> {
+    let find = |name: &str| component.property(name);
+
+    if find("STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED")) {
+        return Ok(());
+    }
+
+    let summary = find("SUMMARY").map(|p| unescape_text(&p.value)).unwrap_or_default();
+    let summary = summary.trim();
+    let Some(dtstart) = find("DTSTART") else {
+        return Ok(());
+    };
+    if summary.is_empty() || is_date_only(dtstart) {
+        return Ok(());
+    }
+
+    let start = TimeValue::parse(dtstart)?;
+    let end = find("DTEND").map(TimeValue::parse).transpose()?;
+    let first = zones.resolve(&start).ok_or_else(|| invalid_time(&dtstart.value))?;
+    let duration = match (&end, find("DURATION")) {
+        (Some(end), _) => zones.resolve(end).ok_or_else(|| invalid_time(&end.naive().to_string()))? - first,
+        (None, Some(duration)) => parse_duration(&duration.value)?,
+        (None, None) => return Ok(()),
+    };
+    if duration <= Duration::zero() {
+        return Ok(());
+    }
+
+    // 定義のないタイムゾーンの時刻は正しく変換できない
+    let unknown_zone = [Some(&start), end.as_ref()]
+        .into_iter()
+        .flatten()
+        .filter_map(TimeValue::tzid)
+        .find(|tzid| !zones.contains(tzid))
+        .map(|tzid| SkipReason::UnknownTimeZone(tzid.to_string()));
+
+    // 期間の前後2日を含めた範囲の回だけを求める（予定と端末のタイムゾーンの差のため）
+    let from = range.start.checked_sub_days(Days::new(2)).unwrap_or(range.start).and_time(NaiveTime::MIN);
+    let to = range.end.checked_add_days(Days::new(2)).unwrap_or(range.end).and_time(NaiveTime::MIN);
+
+    let mut unsupported = None;
+    let mut starts = vec![start.clone()];
+    if let Some(rrule) = find("RRULE")
+        && find("RECURRENCE-ID").is_none()
+    {
+        match rrule.value.parse::<Recurrence>() {
+            Ok(rule) => {
+                let to_utc = |at| zones.resolve(&start.with_naive(at)).map(|t| t.naive_utc());
+                starts = rule
+                    .occurrences(start.naive(), to, to_utc)
+                    .into_iter()
+                    .filter(|at| *at >= from)
+                    .map(|at| start.with_naive(at))
+                    .collect();
+            }
+            Err(_) => unsupported = Some(SkipReason::UnsupportedRecurrence(rrule.value.clone())),
+        }
+    }
+    for rdate in component.properties_named("RDATE") {
+        starts.extend(parse_date_list(rdate, &start)?);
+    }
+
+    let mut excluded = Vec::new();
+    for exdate in component.properties_named("EXDATE") {
+        excluded.extend(parse_date_list(exdate, &start)?.iter().filter_map(|at| zones.resolve(at)));
+    }
+    // 置き換えられた回は、置き換えた予定のほうを取り込む
+    if find("RECURRENCE-ID").is_none()
+        && let Some(uid) = find("UID")
+    {
+        excluded.extend(overridden.iter().filter(|(u, _)| *u == uid.value).map(|(_, at)| *at));
+    }
+
+    let mut starts: Vec<_> = starts
+        .iter()
+        .map(|at| zones.resolve(at).ok_or_else(|| invalid_time(&at.naive().to_string())))
+        .collect::<Result<_, _>>()?;
+    starts.retain(|at| !excluded.contains(at));
+    starts.sort();
+    starts.dedup();
+
+    let description = find("DESCRIPTION")
+        .map(|p| unescape_text(&p.value).trim().to_string())
+        .filter(|d| !d.is_empty());
+    let categories: Vec<String> = component
+        .properties_named("CATEGORIES")
+        .flat_map(|p| split_unescaped(&p.value, ','))
+        .map(|c| unescape_text(&c).trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    for at in starts {
+        // 展開できない繰り返しの予定は、期間内の回があるかもしれないので期間より前に始まっていれば入れる
+        let in_range = match unsupported {
+            Some(_) => at.date_naive() <= range.end,
+            None => range.contains(at.date_naive()),
+        };
+        if !in_range {
+            continue;
+        }
+
+        let event = CalendarEvent {
+            uid: find("UID").map(|p| p.value.clone()),
+            summary: summary.to_string(),
+            description: description.clone(),
+            categories: categories.clone(),
+            start: at,
+            end: at.checked_add_signed(duration).ok_or_else(|| {
+                TimeCheckerError::InvalidCalendar(format!("終了時刻が範囲外です: {}", summary))
+            })?,
+        };
+        match unknown_zone.clone().or_else(|| unsupported.clone()) {
+            Some(reason) => parsed.skipped.push(SkippedEvent { event, reason }),
+            None => parsed.events.push(event),
+        }
+    }
+
+    Ok(())
+}
+
+/// 終日の予定（日付のみ）かどうか
+fn is_date_only(property: &Property) -> bool {
+    property.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) || !property.value.contains('T')
+}
+
+/// RDATE や EXDATE の日時のリストをパースする
+/// 日付のみの値は最初の回と同じ時刻、期間（`VALUE=PERIOD`）は開始時刻にする
+fn parse_date_list(property: &Property, start: &TimeValue) -> Result<Vec<TimeValue>, TimeCheckerError> {
+    property
+        .value
+        .split(',')
+        .map(|value| {
+            let value = value.split('/').next().unwrap_or(value);
+            if value.contains('T') {
+                return TimeValue::parse_value(value, property.param("TZID"));
+            }
+            let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| invalid_time(value))?;
+            Ok(start.with_naive(date.and_time(start.naive().time())))
+        })
+        .collect()
+}
+
+/// 日時を解釈できないエラー
+fn invalid_time(value: &str) -> TimeCheckerError {
+    TimeCheckerError::InvalidCalendar(format!("日時を解釈できません: {}", value))
+}
+
+/// UTC とみなす `TZID`
+const UTC_TIME_ZONES: [&str; 4] = ["UTC", "Etc/UTC", "GMT", "Etc/GMT"];
+
+/// タイムゾーンを変換する前の日時
+#[derive(Debug, Clone, PartialEq)]
+enum TimeValue {
+    /// UTC の時刻
+    Utc(NaiveDateTime),
+
+    /// `TZID` 付きの時刻
+    Zoned(NaiveDateTime, String),
+
+    /// タイムゾーンの指定がない時刻（端末のタイムゾーン）
+    Floating(NaiveDateTime),
+}
+
+impl TimeValue {
+    /// 日時のプロパティをパースする
+    fn parse(property: &Property) -> Result<Self, TimeCheckerError> {
+        Self::parse_value(&property.value, property.param("TZID"))
+    }
+
+    /// `YYYYMMDDTHHMMSSZ`（UTC）または `YYYYMMDDTHHMMSS`（`tzid` のタイムゾーン）をパースする
+    fn parse_value(value: &str, tzid: Option<&str>) -> Result<Self, TimeCheckerError> {
+        let naive = |v| NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S").map_err(|_| invalid_time(value));
+
+        match (value.strip_suffix('Z'), tzid) {
+            (Some(utc), _) => Ok(Self::Utc(naive(utc)?)),
+            (None, Some(tzid)) if UTC_TIME_ZONES.iter().any(|z| z.eq_ignore_ascii_case(tzid)) => {
+                Ok(Self::Utc(naive(value)?))
+            }
+            (None, Some(tzid)) => Ok(Self::Zoned(naive(value)?, tzid.to_string())),
+            (None, None) => Ok(Self::Floating(naive(value)?)),
+        }
+    }
+
+    /// タイムゾーンの中での日時
+    fn naive(&self) -> NaiveDateTime {
+        match self {
+            Self::Utc(naive) | Self::Zoned(naive, _) | Self::Floating(naive) => *naive,
+        }
+    }
+
+    /// 同じタイムゾーンの別の日時
+    fn with_naive(&self, naive: NaiveDateTime) -> Self {
+        match self {
+            Self::Utc(_) => Self::Utc(naive),
+            Self::Zoned(_, tzid) => Self::Zoned(naive, tzid.clone()),
+            Self::Floating(_) => Self::Floating(naive),
+        }
+    }
+
+    /// `TZID`
+    fn tzid(&self) -> Option<&str> {
+        match self {
+            Self::Zoned(_, tzid) => Some(tzid),
+            _ => None,
+        }
+    }
+}
+
+/// カレンダーの VTIMEZONE で定義されたタイムゾーン（`TZID` と標準時・夏時間）
+#[derive(Debug, Default)]
+struct TimeZones(HashMap<String, Vec<Observance>>);
+
+impl TimeZones {
+    /// VCALENDAR の中の VTIMEZONE を読み込む（解釈できない定義は無視する）
+    fn from_calendar(calendar: &Component) -> Self {
+        let zones = calendar
+            .components("VTIMEZONE")
+            .filter_map(|zone| {
+                let tzid = zone.property("TZID")?.value.clone();
+                let observances = zone
+                    .children
+                    .iter()
+                    .filter(|c| c.name == "STANDARD" || c.name == "DAYLIGHT")
+                    .map(Observance::parse)
+                    .collect::<Option<Vec<_>>>()?;
+                (!observances.is_empty()).then_some((tzid, observances))
+            })
+            .collect();
+        Self(zones)
+    }
+
+    /// 定義のあるタイムゾーンかどうか
+    fn contains(&self, tzid: &str) -> bool {
+        self.0.contains_key(tzid)
+    }
+
+    /// 端末のタイムゾーンの時刻にする（定義のないタイムゾーンの時刻は端末のタイムゾーンとみなす）
+    fn resolve(&self, value: &TimeValue) -> Option<DateTime<Local>> {
+        let utc = |naive: NaiveDateTime| Utc.from_utc_datetime(&naive).with_timezone(&Local);
+
+        match value {
+            TimeValue::Utc(naive) => Some(utc(*naive)),
+            TimeValue::Zoned(naive, tzid) if self.contains(tzid) => {
+                let offset = offset_at(&self.0[tzid], *naive)?;
+                shift(*naive, -offset).map(utc)
+            }
+            TimeValue::Zoned(naive, _) | TimeValue::Floating(naive) => {
+                // 夏時間の開始で存在しない時刻は、1時間後の時刻にする
+                Local.from_local_datetime(naive).earliest().or_else(|| {
+                    let later = shift(*naive, 3600)?;
+                    Local.from_local_datetime(&later).earliest()
+                })
+            }
+        }
+    }
+}
+
+/// タイムゾーンの標準時または夏時間（STANDARD / DAYLIGHT）
+#[derive(Debug)]
+struct Observance {
+    /// 最初に切り替わる時刻（切り替わる前のオフセットでの時刻）
+    start: NaiveDateTime,
+
+    /// 切り替わりの繰り返し
+    rule: Option<Recurrence>,
+
+    /// 追加の切り替わりの時刻（RDATE）
+    dates: Vec<NaiveDateTime>,
+
+    /// 切り替わる前のオフセット（秒）
+    offset_from: i32,
+
+    /// 切り替わった後のオフセット（秒）
+    offset_to: i32,
+}
+
+impl Observance {
+    /// STANDARD / DAYLIGHT コンポーネントを読み込む（解釈できなければ None）
+    fn parse(component: &Component) -> Option<Self> {
+        let naive = |v: &str| NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S").ok();
+        let rule = match component.property("RRULE") {
+            Some(rrule) => Some(rrule.value.parse().ok()?),
+            None => None,
+        };
+
+        Some(Self {
+            start: naive(&component.property("DTSTART")?.value)?,
+            rule,
+            dates: component
+                .properties_named("RDATE")
+                .flat_map(|p| p.value.split(','))
+                .map(naive)
+                .collect::<Option<_>>()?,
+            offset_from: parse_offset(&component.property("TZOFFSETFROM")?.value)?,
+            offset_to: parse_offset(&component.property("TZOFFSETTO")?.value)?,
+        })
+    }
+
+    /// `to`（切り替わる前のオフセットでの時刻）までに切り替わる UTC の時刻
+    fn transitions(&self, to: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let to_utc = |at| shift(at, -self.offset_from);
+        let onsets = match &self.rule {
+            Some(rule) => rule.occurrences(self.start, to, to_utc),
+            None => vec![self.start],
+        };
+
+        onsets
+            .into_iter()
+            .chain(self.dates.iter().copied())
+            .filter(|at| *at <= to)
+            .filter_map(to_utc)
+            .collect()
+    }
+}
+
+/// タイムゾーンの中の時刻 `local` に適用されるオフセット（秒）
+fn offset_at(observances: &[Observance], local: NaiveDateTime) -> Option<i32> {
+    let to = local.checked_add_days(Days::new(1))?;
+    let latest = observances
+        .iter()
+        .flat_map(|o| o.transitions(to).into_iter().map(move |at| (at, o.offset_to)))
+        .filter(|&(at, offset)| shift(local, -offset).is_some_and(|utc| at <= utc))
+        .max_by_key(|&(at, _)| at);
+
+    match latest {
+        Some((_, offset)) => Some(offset),
+        // 最初の切り替わりより前は、その切り替わりの前のオフセット
+        None => observances.iter().min_by_key(|o| o.start).map(|o| o.offset_from),
+    }
+}
+
+/// `+0900`、`-0430`、`+053000` 形式のオフセットを秒にする
+fn parse_offset(value: &str) -> Option<i32> {
+    let (sign, digits) = match value.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if !matches!(digits.len(), 4 | 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let part = |i: usize| digits.get(i..i + 2).and_then(|p| p.parse::<i32>().ok()).unwrap_or(0);
+    Some(sign * (part(0) * 3600 + part(2) * 60 + part(4)))
+}
+
+/// 時刻を秒数だけずらす
+fn shift(at: NaiveDateTime, seconds: i32) -> Option<NaiveDateTime> {
+    at.checked_add_signed(Duration::seconds(seconds.into()))
+}
+
+/// `PT1H30M` や `P1DT2H` 形式の期間をパースする
+fn parse_duration(value: &str) -> Result<Duration, TimeCheckerError> {
+    let invalid = || TimeCheckerError::InvalidCalendar(format!("期間を解釈できません: {}", value));
+
+    let rest = value.strip_prefix('+').unwrap_or(value).strip_prefix('P').ok_or_else(invalid)?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' if number.is_empty() => {}
+            'W' | 'D' | 'H' | 'M' | 'S' => {
+                let n: i64 = number.parse().map_err(|_| invalid())?;
+                // 大きすぎる値は範囲外としてエラーにする
+                let part = match c {
+                    'W' => Duration::try_weeks(n),
+                    'D' => Duration::try_days(n),
+                    'H' => Duration::try_hours(n),
+                    'M' => Duration::try_minutes(n),
+                    _ => Duration::try_seconds(n),
+                };
+                total = part.and_then(|p| total.checked_add(&p)).ok_or_else(invalid)?;
+                number.clear();
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    if number.is_empty() { Ok(total) } else { Err(invalid()) }
+}
+
+/// 折り返された行（次の行が空白で始まる）をつなげる
+fn unfold_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.split('\n').map(|l| l.strip_suffix('\r').unwrap_or(l)) {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// エスケープされていない区切り文字で分割する
+fn split_unescaped(value: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut escaped = false;
+    for c in value.chars() {
+        if c == separator && !escaped {
+            parts.push(String::new());
+            continue;
+        }
+        escaped = c == '\\' && !escaped;
+        if let Some(last) = parts.last_mut() {
+            last.push(c);
+        }
+    }
+    parts
+}
+
+/// テキストの値のエスケープを解除する
+pub fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// テキストの値をエスケープする（`\`、`;`、`,`、改行）
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 75バイトを超える行を折り返して CRLF で書き出す（マルチバイト文字の途中では折り返さない）
+pub fn write_line<W: Write + ?Sized>(out: &mut W, line: &str) -> io::Result<()> {
+    let mut rest = line;
+    // 折り返した行は先頭の空白1バイトぶん短くする
+    let mut limit = MAX_LINE_OCTETS;
+    while rest.len() > limit {
+        let mut split = limit;
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        let (head, tail) = rest.split_at(split);
+        write!(out, "{}\r\n ", head)?;
+        rest = tail;
+        limit = MAX_LINE_OCTETS - 1;
+    }
+    write!(out, "{}\r\n", rest)
+}
+
+/// 日時を UTC の `YYYYMMDDTHHMMSSZ` 形式にする
+fn format_utc(time: DateTime<Local>) -> String {
+    time.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
@@ -0,0 +1,365 @@
+// 追記専用のジャーナル（JSON Lines）による保存先
+// 変更のたびにファイル全体を書き直さず、変更内容をイベントとして追記する
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use crate::clock::{Clock, SystemClock};
+use crate::data::{self, Entries, TimeEntry};
+use crate::error::TimeCheckerError;
+use crate::lock::{DataLock, DEFAULT_LOCK_TIMEOUT};
+use crate::storage::Storage;
+
+/// ジャーナルファイルの名前
+pub const JOURNAL_FILE_NAME: &str = "journal.jsonl";
+
+/// スナップショットファイルの名前
+pub const SNAPSHOT_FILE_NAME: &str = "snapshot.json";
+
+/// コンパクション済みのイベントを残しておくファイルの名前
+pub const HISTORY_FILE_NAME: &str = "journal.history.jsonl";
+
+/// ロックファイルの名前
+const LOCK_FILE_NAME: &str = "journal.lock";
+
+/// ジャーナルのイベント数がこれを超えたらスナップショットにまとめる
+pub const DEFAULT_COMPACT_THRESHOLD: usize = 500;
+
+/// エントリに対する変更
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// タスクの開始（進行中のエントリの追加）
+    Start { entry: TimeEntry },
+    /// 進行中のタスクの停止
+    Stop { id: u64, end: DateTime<Local> },
+    /// 終了済みのエントリの追加
+    Add { entry: TimeEntry },
+    /// エントリの変更（変更後のエントリ全体を持つ）
+    Edit { entry: TimeEntry },
+    /// エントリの削除
+    Delete { id: u64 },
+}
+
+/// ジャーナルの1行（イベントと記録した時刻）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct JournalRecord {
+    /// イベントを記録した時刻
+    pub at: DateTime<Local>,
+
+    /// イベントの内容
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+impl JournalEvent {
+    /// イベントをエントリに適用する
+    /// すべて「上書き」か「削除」なので、同じイベントを2回適用しても結果は変わらない
+    pub fn apply(&self, entries: &mut Vec<TimeEntry>) {
+        match self {
+            JournalEvent::Start { entry } | JournalEvent::Add { entry } | JournalEvent::Edit { entry } => {
+                match entries.iter_mut().find(|e| e.id == entry.id) {
+                    Some(existing) => *existing = entry.clone(),
+                    None => entries.push(entry.clone()),
+                }
+            }
+            JournalEvent::Stop { id, end } => {
+                if let Some(entry) = entries.iter_mut().find(|e| e.id == *id) {
+                    entry.end = Some(*end);
+                }
+            }
+            JournalEvent::Delete { id } => entries.retain(|e| e.id != *id),
+        }
+    }
+}
+
+/// 変更前と変更後のエントリを比べて、その間の変更をイベントとして列挙する
+pub fn diff_events(before: &[TimeEntry], after: &[TimeEntry]) -> Vec<JournalEvent> {
+    let old: HashMap<u64, &TimeEntry> = before.iter().map(|e| (e.id, e)).collect();
+    let new: HashMap<u64, &TimeEntry> = after.iter().map(|e| (e.id, e)).collect();
+
+    let deleted = before
+        .iter()
+        .filter(|e| !new.contains_key(&e.id))
+        .map(|e| JournalEvent::Delete { id: e.id });
+
+    let changed = after.iter().filter_map(|entry| match old.get(&entry.id) {
+        None if entry.end.is_none() => Some(JournalEvent::Start { entry: entry.clone() }),
+        None => Some(JournalEvent::Add { entry: entry.clone() }),
+        Some(previous) if *previous == entry => None,
+        Some(previous) => match entry.end {
+            // 終了時刻が付いただけなら停止
+            Some(end) if previous.end.is_none() && **previous == TimeEntry { end: None, ..entry.clone() } => {
+                Some(JournalEvent::Stop { id: entry.id, end })
+            }
+            _ => Some(JournalEvent::Edit { entry: entry.clone() }),
+        },
+    });
+
+    deleted.chain(changed).collect()
+}
+
+/// スナップショットとジャーナルから読み込んだ状態
+struct JournalState {
+    entries: Entries,
+
+    /// ジャーナルのイベント数
+    events: usize,
+
+    /// ジャーナルのうち正しく読めた部分の長さ（途中で切れた行を除く）
+    valid_len: u64,
+}
+
+/// 追記専用のジャーナルにエントリを保存する保存先
+/// 読み込み時はスナップショットにジャーナルのイベントを順に適用して復元する
+pub struct JournalStore {
+    dir: PathBuf,
+    clock: Arc<dyn Clock>,
+    lock_timeout: StdDuration,
+    compact_threshold: usize,
+}
+
+impl JournalStore {
+    /// 指定ディレクトリのジャーナルを使う保存先を作成（システムの時計を使用）
+    pub fn new(dir: PathBuf) -> Self {
+        Self::with_clock(dir, Arc::new(SystemClock))
+    }
+
+    /// 時計を指定して作成（イベントの記録時刻に使う）
+    pub fn with_clock(dir: PathBuf, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            dir,
+            clock,
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+            compact_threshold: DEFAULT_COMPACT_THRESHOLD,
+        }
+    }
+
+    /// ロックが取得できるまで待つ時間を設定
+    pub fn with_lock_timeout(mut self, timeout: StdDuration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    /// スナップショットにまとめるイベント数を設定
+    pub fn with_compact_threshold(mut self, threshold: usize) -> Self {
+        self.compact_threshold = threshold;
+        self
+    }
+
+    /// ジャーナルファイルのパス
+    pub fn journal_file(&self) -> PathBuf {
+        self.dir.join(JOURNAL_FILE_NAME)
+    }
+
+    /// スナップショットファイルのパス
+    pub fn snapshot_file(&self) -> PathBuf {
+        self.dir.join(SNAPSHOT_FILE_NAME)
+    }
+
+    /// コンパクション済みのイベントを残しておくファイルのパス
+    pub fn history_file(&self) -> PathBuf {
+        self.dir.join(HISTORY_FILE_NAME)
+    }
+
+    /// これまでのすべての変更（コンパクション済みのものも含む）を古い順に取得
+    pub fn history(&self) -> Result<Vec<JournalRecord>, TimeCheckerError> {
+        let mut records = read_records(&self.history_file())?.0;
+        records.extend(read_records(&self.journal_file())?.0);
+        Ok(records)
+    }
+
+    /// ジャーナルのイベントをスナップショットにまとめる
+    pub fn compact(&self) -> Result<(), TimeCheckerError> {
+        let _lock = self.lock()?;
+        let state = self.read_state()?;
+        self.compact_locked(&state.entries)
+    }
+
+    fn lock(&self) -> Result<DataLock, TimeCheckerError> {
+        DataLock::acquire(&self.dir.join(LOCK_FILE_NAME), self.lock_timeout)
+    }
+
+    /// スナップショットを読み込み、ジャーナルのイベントを適用する
+    fn read_state(&self) -> Result<JournalState, TimeCheckerError> {
+        let mut entries = read_snapshot(&self.snapshot_file())?;
+        let (records, valid_len) = read_records(&self.journal_file())?;
+
+        // 追加した後で削除されたエントリのIDも使わないよう、追加されたIDの次から採番する
+        let mut next_id = entries.next_id();
+        for record in &records {
+            record.event.apply(&mut entries);
+            if let JournalEvent::Start { entry } | JournalEvent::Add { entry } = &record.event {
+                next_id = next_id.max(entry.id + 1);
+            }
+        }
+
+        let entries = Entries::new(entries.into_vec(), next_id);
+        Ok(JournalState { entries, events: records.len(), valid_len })
+    }
+
+    /// イベントをジャーナルの末尾に追記して fsync する
+    fn append(&self, events: Vec<JournalEvent>, valid_len: u64) -> Result<(), TimeCheckerError> {
+        let at = self.clock.now();
+        let mut buf = Vec::new();
+        for event in events {
+            serde_json::to_writer(&mut buf, &JournalRecord { at, event })
+                .map_err(|e| TimeCheckerError::DataSaveError(format!("シリアライズに失敗: {}", e)))?;
+            buf.push(b'\n');
+        }
+
+        fs::create_dir_all(&self.dir).map_err(save_error)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.journal_file())
+            .map_err(save_error)?;
+
+        // 前回の書き込みが途中で止まって残った不完全な行は捨ててから追記する
+        if file.metadata().map_err(save_error)?.len() > valid_len {
+            file.set_len(valid_len).map_err(save_error)?;
+        }
+
+        file.write_all(&buf).map_err(save_error)?;
+        file.sync_data().map_err(save_error)
+    }
+
+    /// スナップショットを書き出し、ジャーナルのイベントを履歴ファイルへ移す（ロックを取得済みであること）
+    /// 途中で中断してもイベントの適用は冪等なので、読み込み結果は変わらない
+    fn compact_locked(&self, entries: &Entries) -> Result<(), TimeCheckerError> {
+        fs::create_dir_all(&self.dir).map_err(save_error)?;
+
+        let json = data::serialize_data(entries)?;
+        data::write_atomic(&self.snapshot_file(), json.as_bytes()).map_err(save_error)?;
+
+        // 監査用にイベントは捨てずに履歴ファイルへ追記する
+        let journal = read_complete_lines(&self.journal_file())?;
+        if !journal.is_empty() {
+            let history = read_complete_lines(&self.history_file())?;
+            // 前回のコンパクションが履歴への追記の後、ジャーナルを空にする前に中断していたら追記済み
+            if !history.ends_with(&journal) {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.history_file())
+                    .map_err(save_error)?;
+                // 書き込み途中で止まって残った不完全な行は捨ててから追記する
+                if file.metadata().map_err(save_error)?.len() > history.len() as u64 {
+                    file.set_len(history.len() as u64).map_err(save_error)?;
+                }
+                file.write_all(&journal).map_err(save_error)?;
+                file.sync_data().map_err(save_error)?;
+            }
+        }
+
+        data::write_atomic(&self.journal_file(), b"").map_err(save_error)
+    }
+}
+
+impl Storage for JournalStore {
+    fn load_entries(&self) -> Result<Entries, TimeCheckerError> {
+        Ok(self.read_state()?.entries)
+    }
+
+    /// すべてのエントリを入れ替える（スナップショットとして書き出す）
+    fn save_entries(&self, entries: &Entries) -> Result<(), TimeCheckerError> {
+        let mut entries = entries.clone();
+        entries.assign_ids();
+
+        let _lock = self.lock()?;
+        self.compact_locked(&entries)
+    }
+
+    /// ロックを保持したまま 読み込み → 変更 → 差分のイベントの追記 を行う
+    fn update(
+        &self,
+        update: &mut dyn FnMut(&mut Entries) -> Result<(), TimeCheckerError>,
+    ) -> Result<(), TimeCheckerError> {
+        let _lock = self.lock()?;
+
+        let state = self.read_state()?;
+        let mut entries = state.entries.clone();
+        update(&mut entries)?;
+        entries.assign_ids();
+
+        let events = diff_events(&state.entries, &entries);
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let total = state.events + events.len();
+        self.append(events, state.valid_len)?;
+
+        if total > self.compact_threshold {
+            self.compact_locked(&entries)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// スナップショットを読み込む（なければ空）
+fn read_snapshot(path: &Path) -> Result<Entries, TimeCheckerError> {
+    if !path.exists() {
+        return Ok(Entries::default());
+    }
+
+    let content = fs::read_to_string(path).map_err(load_error)?;
+    if content.trim().is_empty() {
+        return Ok(Entries::default());
+    }
+
+    // data.json と同じバージョン付きの形式
+    data::parse_data(&content).map_err(|e| match e {
+        TimeCheckerError::DataLoadError(msg) => TimeCheckerError::DataLoadError(format!("{}: {}", path.display(), msg)),
+        e => e,
+    })
+}
+
+/// JSON Lines のイベントを読み込み、イベントと正しく読めた部分のバイト数を返す
+/// 最後の行が改行で終わっていない場合は書き込み途中で止まったものとして無視する
+fn read_records(path: &Path) -> Result<(Vec<JournalRecord>, u64), TimeCheckerError> {
+    let bytes = read_complete_lines(path)?;
+    let complete = std::str::from_utf8(&bytes).map_err(|e| {
+        TimeCheckerError::DataLoadError(format!("{} が UTF-8 ではありません: {}", path.display(), e))
+    })?;
+
+    let records = complete
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                TimeCheckerError::DataLoadError(format!("{} の {} 行目が読み込めません: {}", path.display(), i + 1, e))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((records, complete.len() as u64))
+}
+
+/// ファイルを最後の改行までバイト列のまま読み込む（なければ空）
+/// 書き込み途中で止まった行はマルチバイト文字の途中で切れていることがあるので、文字列にする前に捨てる
+fn read_complete_lines(path: &Path) -> Result<Vec<u8>, TimeCheckerError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut bytes = fs::read(path).map_err(load_error)?;
+    let len = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    bytes.truncate(len);
+    Ok(bytes)
+}
+
+fn load_error(e: std::io::Error) -> TimeCheckerError {
+    TimeCheckerError::DataLoadError(format!("ジャーナルの読み込みに失敗: {}", e))
+}
+
+fn save_error(e: std::io::Error) -> TimeCheckerError {
+    TimeCheckerError::DataSaveError(format!("ジャーナルの書き込みに失敗: {}", e))
+}
@@ -2,7 +2,23 @@
 // ライブラリとしてモジュールを公開（テスト用）
 
 pub mod cli;
+pub mod clock;
 pub mod data;
 pub mod tracker;
 pub mod completion;
+pub mod config;
 pub mod error;
+pub mod export;
+pub mod filter;
+pub mod history;
+pub mod ical;
+pub mod journal;
+pub mod lock;
+pub mod period;
+pub mod recurrence;
+pub mod report;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod storage;
+pub mod summary;
+pub mod timespec;
@@ -0,0 +1,64 @@
+// データファイルの排他ロック（複数のプロセスからの同時更新を防ぐ）
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::error::TimeCheckerError;
+
+/// ロックが取得できるまで待つ時間のデフォルト
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// ロックの取得を再試行する間隔
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 取得済みのロック（ドロップ時に解放される）
+/// OSのアドバイザリロックを使うため、プロセスが異常終了してもロックは残らない
+#[derive(Debug)]
+pub struct DataLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl DataLock {
+    /// ロックファイルの排他ロックを取得する
+    /// 他のプロセスがロックしている場合は `timeout` まで待ち、それでも取得できなければエラー
+    pub fn acquire(path: &Path, timeout: Duration) -> Result<Self, TimeCheckerError> {
+        let lock_error = |e: std::io::Error| {
+            TimeCheckerError::DataSaveError(format!("ロックファイル {} を開けません: {}", path.display(), e))
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(lock_error)?;
+        }
+
+        // ロックファイルの中身は使わないので、既存のファイルを切り詰めずに開く
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(lock_error)?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match file.try_lock() {
+                Ok(()) => {
+                    return Ok(Self { _file: file, path: path.to_path_buf() });
+                }
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    thread::sleep(RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(TimeCheckerError::LockTimeout(path.display().to_string()));
+                }
+                Err(TryLockError::Error(e)) => return Err(lock_error(e)),
+            }
+        }
+    }
+
+    /// ロックファイルのパス
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
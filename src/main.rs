@@ -1,74 +1,418 @@
 // エントリーポイント
 
-use clap::Parser;
+use chrono::{DateTime, Local, NaiveDate};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process;
-use time_checker::cli::{Cli, Commands};
-use time_checker::data::DataStore;
-use time_checker::tracker::Tracker;
+use std::time::Duration;
+use time_checker::cli::{Cli, Commands, ImportFormat, Shell};
+use time_checker::completion;
+use time_checker::config::{self, Config, DataDirSource, DataLocation, StorageBackend};
+use time_checker::data::{self, DataStore, TimeEntry};
+use time_checker::error::TimeCheckerError;
+use time_checker::export::{self, ExportFormat, ExportKind, Granularity};
+use time_checker::filter::TaskFilter;
+use time_checker::history::{self, OperationHistory};
+use time_checker::ical;
+use time_checker::period::Period;
+use time_checker::report::{MarkdownRenderer, Report, ReportFormat};
+use time_checker::storage::{self, Storage};
+use time_checker::summary::{
+    CurrentStatus, EntryDetail, PeriodEntries, PeriodSummary, PeriodTagSummary, StopResult, Summary, TaskNode, TaskTree,
+};
+use time_checker::timespec;
+use time_checker::tracker::{EntryEdit, ImportResult, SkipReason, SkippedEntry, Tracker};
 
-fn main() {
-    // データファイルのパスを決定
-    let data_dir = dirs::home_dir()
-        .expect("ホームディレクトリの取得に失敗")
-        .join(".time-checker");
+/// 設定された保存先を使うTracker
+type AppTracker = Tracker<Box<dyn Storage>>;
 
-    let data_file = data_dir.join("data.json");
+/// `continue` で番号を省略したときに表示する候補の数
+const RECENT_TASK_LIMIT: usize = 9;
 
-    // DataStoreとTrackerを初期化
-    let store = DataStore::new(data_file);
-    let tracker = Tracker::new(store);
+/// show の出力形式
+enum SummaryFormat {
+    /// 端末向けのテキスト
+    Text,
+    /// Markdownのレポート
+    Markdown(MarkdownRenderer),
+    /// JSON（--json）
+    Json,
+}
+
+fn main() {
+    // 補完スクリプトからの呼び出しであれば補完候補を出力して終了
+    CompleteEnv::with_factory(Cli::command)
+        .var(completion::COMPLETE_ENV_VAR)
+        .complete();
 
     // CLIコマンドをパース
     let cli = Cli::parse();
+    let json = cli.json;
 
     // コマンドを実行
-    let result = match cli.command {
-        Commands::Start { task, note } => handle_start(&tracker, task, note),
-        Commands::Stop => handle_stop(&tracker),
-        Commands::Status => handle_status(&tracker),
-        Commands::Show { period } => handle_show(&tracker, period),
-    };
+    let result = run(cli);
 
-    // エラーハンドリング
+    // エラーハンドリング（--json ではエラーもJSONで出す）
     if let Err(e) = result {
-        eprintln!("エラー: {}", e);
+        if json {
+            eprintln!("{}", serde_json::json!({ "error": e.to_string() }));
+        } else {
+            eprintln!("エラー: {}", e);
+        }
         process::exit(1);
     }
 }
 
+/// 設定を読み込んでコマンドを実行
+fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let json = cli.json;
+    if json && matches!(cli.command, Commands::Completion { .. } | Commands::Export { .. }) {
+        Cli::command()
+            .error(ErrorKind::ArgumentConflict, "--json は completion / export では使えません")
+            .exit();
+    }
+
+    // completionはデータファイルを必要としない
+    if let Commands::Completion { shell } = cli.command {
+        return handle_completion(shell);
+    }
+
+    // データファイルのパスを決定（--data-dir > 環境変数 > config.toml > 標準の場所）
+    let config = Config::load()?;
+    let env_dir = env::var_os(config::DATA_DIR_ENV).map(PathBuf::from);
+    let location = config::resolve_data_dir(cli.data_dir, env_dir, &config)?;
+
+    // 旧バージョンの ~/.time-checker があれば標準の場所へ移行
+    if location.source == DataDirSource::Default
+        && let Some(legacy) = config::legacy_data_dir()
+        && config::migrate_legacy_data(&legacy, &location.dir)?
+    {
+        eprintln!(
+            "データを {} から {} へ移行しました",
+            legacy.display(),
+            location.dir.display()
+        );
+    }
+
+    // 設定ファイルの除外パターン
+    let exclude = TaskFilter::parse(&config.filters.exclude_tasks)?;
+
+    // migrateは保存先を自分で開く
+    if let Commands::Migrate { to, from, file, force } = cli.command {
+        let from = from.unwrap_or(config.storage.backend);
+        return handle_migrate(&location, from, to, file, force, json);
+    }
+
+    // 設定された保存先でTrackerを初期化
+    let store = storage::open(config.storage.backend, &location)?;
+    let history = OperationHistory::new(location.dir.join(history::UNDO_FILE_NAME));
+    let tracker = Tracker::new(store).with_history(history);
+
+    match cli.command {
+        Commands::Start { task, tags, note, at, ago } => handle_start(&tracker, task, tags, note, at, ago, json),
+        Commands::Stop { at, ago } => handle_stop(&tracker, at, ago, &exclude, json),
+        Commands::Continue { nth } => handle_continue(&tracker, nth, json),
+        Commands::Pause => handle_pause(&tracker, json),
+        Commands::Resume => handle_resume(&tracker, json),
+        Commands::Add { task, tags, from, to, date, note } => {
+            handle_add(&tracker, task, tags, from, to, date, note, json)
+        }
+        Commands::Status => handle_status(&tracker, &exclude, json),
+        Commands::Show {
+            period, exclude: cli_exclude, no_exclude, tags, by_tag, tree, depth, format, style, notes, no_notes,
+        } => {
+            // 除外設定の優先順位: --no-exclude > --exclude > 設定ファイル
+            let exclude = if no_exclude {
+                TaskFilter::none()
+            } else if !cli_exclude.is_empty() {
+                TaskFilter::parse_args(&cli_exclude)?
+            } else {
+                exclude
+            };
+            // --depth は --tree を含む（--tree のみなら畳まない）
+            let tree = depth.map(|d| d as usize).or(tree.then_some(usize::MAX));
+            let format = match format {
+                ReportFormat::Markdown if json => {
+                    Cli::command()
+                        .error(ErrorKind::ArgumentConflict, "--format markdown は --json と併用できません")
+                        .exit();
+                }
+                ReportFormat::Markdown if by_tag || tree.is_some() => {
+                    Cli::command()
+                        .error(ErrorKind::ArgumentConflict, "--format markdown は --by-tag / --tree / --depth と併用できません")
+                        .exit();
+                }
+                // --style / --notes / --no-notes は設定ファイルの [report] より優先
+                ReportFormat::Markdown => SummaryFormat::Markdown(
+                    MarkdownRenderer::new(&config.report)
+                        .with_style(style.unwrap_or(config.report.style))
+                        .with_notes(!no_notes && (notes || config.report.notes)),
+                ),
+                ReportFormat::Text if json => SummaryFormat::Json,
+                ReportFormat::Text => SummaryFormat::Text,
+            };
+            handle_show(&tracker, period, &exclude, &tags, by_tag, tree, format)
+        }
+        Commands::List { period, tags } => handle_list(&tracker, period, &tags, json),
+        Commands::Export { kind, period, format, per, tags, no_exclude, output, bom } => {
+            if format == ExportFormat::Ics && (kind != ExportKind::Entries || bom) {
+                Cli::command()
+                    .error(ErrorKind::ArgumentConflict, "--format ics は entries のみで、--bom とは併用できません")
+                    .exit();
+            }
+            let exclude = if no_exclude { TaskFilter::none() } else { exclude };
+            let mut out = open_output(output.as_deref(), bom)?;
+            match format {
+                ExportFormat::Csv => handle_export(&tracker, kind, period, per, &tags, &exclude, &mut out)?,
+                ExportFormat::Ics => {
+                    let calendar_id = ical::calendar_id(&location.dir)?;
+                    handle_ics_export(&tracker, period, &tags, &calendar_id, &mut out)?
+                }
+            }
+            if let Some(path) = output {
+                eprintln!("{} に出力しました", path.display());
+            }
+            Ok(())
+        }
+        Commands::Import { file, period, format, patterns, tags, dry_run } => {
+            let content = std::fs::read_to_string(&file).map_err(|e| {
+                TimeCheckerError::DataLoadError(format!("{} の読み込みに失敗: {}", file.display(), e))
+            })?;
+            let period: Period = period.parse()?;
+            let range = period.resolve(tracker.clock().today());
+            let calendar = match format {
+                ImportFormat::Ics => ical::parse_calendar(&content, &range)?,
+            };
+            let filter = TaskFilter::parse_args(&patterns)?;
+            handle_import(&tracker, calendar, &filter, tags, dry_run, json)
+        }
+        Commands::Edit { id, task, note, start, end, tags } => {
+            handle_edit(&tracker, id, task, note, start, end, tags, json)
+        }
+        Commands::Delete { id } => handle_delete(&tracker, id, json),
+        Commands::Undo { count } => handle_undo(&tracker, count, json),
+        Commands::Redo { count } => handle_redo(&tracker, count, json),
+        // 上で処理済み
+        Commands::Completion { .. } | Commands::Migrate { .. } => Ok(()),
+    }
+}
+
 /// startコマンドの処理
-fn handle_start(tracker: &Tracker, task: String, note: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
-    tracker.start_task(task.clone(), note)?;
-    println!("タスクを開始しました: {}", task);
+fn handle_start(
+    tracker: &AppTracker,
+    task: String,
+    tags: Vec<String>,
+    note: Option<String>,
+    at: Option<String>,
+    ago: Option<String>,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let at = timespec::resolve_at(at.as_deref(), ago.as_deref(), tracker.clock().now())?;
+    let started = tracker.start_tagged_task_at(task, note, tags, at)?;
+    if json {
+        return print_json(&EntryDetail::new(started, tracker.clock().now()));
+    }
+
+    println!(
+        "タスクを開始しました: {}{} ({})",
+        started.task,
+        format_tags(&started.tags),
+        at.format("%H:%M")
+    );
     Ok(())
 }
 
 /// stopコマンドの処理
-fn handle_stop(tracker: &Tracker) -> Result<(), Box<dyn std::error::Error>> {
-    tracker.stop_task()?;
+fn handle_stop(
+    tracker: &AppTracker,
+    at: Option<String>,
+    ago: Option<String>,
+    exclude: &TaskFilter,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let at = timespec::resolve_at(at.as_deref(), ago.as_deref(), tracker.clock().now())?;
+    let stopped = tracker.stop_task_at(at)?;
+    if json {
+        let stopped = EntryDetail::new(stopped, tracker.clock().now());
+        let today = period_summary(tracker, Period::Today, exclude)?;
+        return print_json(&StopResult { stopped, today });
+    }
+
     println!("タスクを停止しました");
     println!();
 
     // 今日のサマリーを表示
-    display_summary(tracker)?;
+    display_summary(tracker, Period::Today, exclude, &[], None)?;
+    Ok(())
+}
+
+/// continueコマンドの処理
+fn handle_continue(tracker: &AppTracker, nth: Option<usize>, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    // JSONで出力する場合は選択肢を表示せず直前のタスクを選ぶ
+    let nth = match nth {
+        Some(nth) => nth,
+        None if json => 1,
+        None => pick_recent_task(tracker)?,
+    };
+
+    let entry = tracker.continue_task(nth)?;
+    if json {
+        return print_json(&EntryDetail::new(entry, tracker.clock().now()));
+    }
+
+    println!(
+        "タスクを開始しました: {}{} ({})",
+        entry.task,
+        format_tags(&entry.tags),
+        entry.start.format("%H:%M")
+    );
+    if let Some(ref note) = entry.note {
+        println!("メモ: {}", note);
+    }
+    Ok(())
+}
+
+/// 最近のタスクを一覧表示して番号を選んでもらう
+/// 端末から実行されていない場合（スクリプトなど）は直前のタスクを選ぶ
+fn pick_recent_task(tracker: &AppTracker) -> Result<usize, Box<dyn std::error::Error>> {
+    if !io::stdin().is_terminal() {
+        return Ok(1);
+    }
+
+    let candidates = tracker.recent_tasks(RECENT_TASK_LIMIT)?;
+    if candidates.len() <= 1 {
+        return Ok(1);
+    }
+
+    println!("最近のタスク:");
+    for (number, entry) in (1..).zip(&candidates) {
+        let last = entry.end.unwrap_or(entry.start).format("%m/%d %H:%M");
+        match entry.note {
+            Some(ref note) => println!("  {}) {}{} ({}) - {}", number, entry.task, format_tags(&entry.tags), note, last),
+            None => println!("  {}) {}{} - {}", number, entry.task, format_tags(&entry.tags), last),
+        }
+    }
+    print!("番号を選んでください [1]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().lock().read_line(&mut input)?;
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(1);
+    }
+
+    match input.parse::<usize>() {
+        Ok(number) if (1..=candidates.len()).contains(&number) => Ok(number),
+        _ => Err(TimeCheckerError::InvalidSelection(input.to_string()).into()),
+    }
+}
+
+/// pauseコマンドの処理
+fn handle_pause(tracker: &AppTracker, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let paused = tracker.pause_task()?;
+    if json {
+        let worked = tracker.get_worked_time(&paused)?.to_std().unwrap_or_default();
+        return print_json(&EntryDetail::new(paused, tracker.clock().now()).with_worked(worked));
+    }
+
+    println!("タスクを一時停止しました: {}", paused.task);
+    println!("再開するには time-checker resume を実行してください");
+    Ok(())
+}
+
+/// resumeコマンドの処理
+fn handle_resume(tracker: &AppTracker, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let resumed = tracker.resume_task()?;
+    if json {
+        let worked = tracker.get_worked_time(&resumed)?.to_std().unwrap_or_default();
+        return print_json(&EntryDetail::new(resumed, tracker.clock().now()).with_worked(worked));
+    }
+
+    println!(
+        "タスクを再開しました: {}{} ({})",
+        resumed.task,
+        format_tags(&resumed.tags),
+        resumed.start.format("%H:%M")
+    );
+    Ok(())
+}
+
+/// addコマンドの処理
+#[allow(clippy::too_many_arguments)]
+fn handle_add(
+    tracker: &AppTracker,
+    task: String,
+    tags: Vec<String>,
+    from: String,
+    to: String,
+    date: Option<NaiveDate>,
+    note: Option<String>,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let date = date.unwrap_or_else(|| tracker.clock().today());
+    let (start, end) = timespec::parse_time_range(&from, &to, date)?;
+
+    let added = tracker.add_tagged_entry(task, note, tags, start, end)?;
+    if json {
+        return print_json(&EntryDetail::new(added, tracker.clock().now()));
+    }
+
+    println!("エントリを追加しました:");
+    print_entry(&added, tracker.clock().now(), true);
     Ok(())
 }
 
 /// statusコマンドの処理
-fn handle_status(tracker: &Tracker) -> Result<(), Box<dyn std::error::Error>> {
+fn handle_status(tracker: &AppTracker, exclude: &TaskFilter, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if json {
+        return print_status_json(tracker, exclude);
+    }
+
     // 現在のタスクを表示
     if let Some(current) = tracker.store().get_current_task()? {
-        let elapsed = chrono::Local::now().signed_duration_since(current.start);
+        let elapsed = tracker.clock().now().signed_duration_since(current.start);
         let hours = elapsed.num_hours();
         let minutes = elapsed.num_minutes() % 60;
 
         println!("進行中のタスク: {}", current.task);
         println!("開始時刻: {}", current.start.format("%H:%M"));
         println!("経過時間: {}時間{}分", hours, minutes);
+        if !current.tags.is_empty() {
+            println!("タグ:{}", format_tags(&current.tags));
+        }
+
+        // 一時停止から再開したタスクは再開前の分も含めた作業時間を表示
+        if current.resumed_from.is_some() {
+            let worked = tracker.get_worked_time(&current)?;
+            println!("作業時間（一時停止を除く）: {}", format_duration(worked.to_std().unwrap_or_default()));
+        }
 
         if let Some(ref note) = current.note {
             println!("メモ: {}", note);
         }
+    } else if let Some(paused) = tracker.get_paused_task()? {
+        let worked = tracker.get_worked_time(&paused)?;
+
+        println!("一時停止中のタスク: {}", paused.task);
+        if !paused.tags.is_empty() {
+            println!("タグ:{}", format_tags(&paused.tags));
+        }
+        if let Some(end) = paused.end {
+            println!("一時停止した時刻: {}", end.format("%H:%M"));
+        }
+        println!("作業時間（一時停止を除く）: {}", format_duration(worked.to_std().unwrap_or_default()));
+
+        if let Some(ref note) = paused.note {
+            println!("メモ: {}", note);
+        }
     } else {
         println!("進行中のタスクはありません");
     }
@@ -76,56 +420,645 @@ fn handle_status(tracker: &Tracker) -> Result<(), Box<dyn std::error::Error>> {
     println!();
 
     // 今日のサマリーを表示
-    display_summary(tracker)?;
+    display_summary(tracker, Period::Today, exclude, &[], None)?;
     Ok(())
 }
 
+/// statusコマンドの結果をJSONで出力
+fn print_status_json(tracker: &AppTracker, exclude: &TaskFilter) -> Result<(), Box<dyn std::error::Error>> {
+    let now = tracker.clock().now();
+    let current = tracker.store().get_current_task()?;
+    let paused = match current {
+        Some(_) => None,
+        None => tracker.get_paused_task()?,
+    };
+
+    // 一時停止から再開したタスクと一時停止中のタスクには一時停止を除いた作業時間を含める
+    let detail = |entry: TimeEntry, worked: bool| -> Result<EntryDetail, TimeCheckerError> {
+        if !worked {
+            return Ok(EntryDetail::new(entry, now));
+        }
+        let duration = tracker.get_worked_time(&entry)?.to_std().unwrap_or_default();
+        Ok(EntryDetail::new(entry, now).with_worked(duration))
+    };
+    let current = current.map(|c| detail(c.clone(), c.resumed_from.is_some())).transpose()?;
+    let paused = paused.map(|p| detail(p, true)).transpose()?;
+    let today = period_summary(tracker, Period::Today, exclude)?;
+
+    print_json(&CurrentStatus { current, paused, today })
+}
+
 /// showコマンドの処理
-fn handle_show(tracker: &Tracker, period: String) -> Result<(), Box<dyn std::error::Error>> {
-    match period.as_str() {
-        "today" => {
-            display_summary(tracker)?;
-            Ok(())
+fn handle_show(
+    tracker: &AppTracker,
+    period: String,
+    exclude: &TaskFilter,
+    tags: &[String],
+    by_tag: bool,
+    tree: Option<usize>,
+    format: SummaryFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let period: Period = period.parse()?;
+    match format {
+        SummaryFormat::Markdown(renderer) => display_markdown_report(tracker, period, exclude, tags, &renderer)?,
+        SummaryFormat::Json => print_summary_json(tracker, period, exclude, tags, by_tag, tree)?,
+        SummaryFormat::Text if by_tag => display_tag_summary(tracker, period, exclude, tags)?,
+        SummaryFormat::Text => display_summary(tracker, period, exclude, tags, tree)?,
+    }
+    Ok(())
+}
+
+/// showコマンドの結果をJSONで出力
+fn print_summary_json(
+    tracker: &AppTracker,
+    period: Period,
+    exclude: &TaskFilter,
+    tags: &[String],
+    by_tag: bool,
+    tree: Option<usize>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let range = period.resolve(tracker.clock().today());
+
+    if by_tag {
+        let summary = tracker.get_tag_summary(range, tags, exclude)?;
+        return print_json(&PeriodTagSummary::new(range, tags, &summary));
+    }
+
+    let summary = Summary::new(tracker.get_tagged_summary(range, tags)?, exclude);
+    let mut output = PeriodSummary::new(range, tags, &summary);
+    if let Some(depth) = tree {
+        output = output.with_tree(TaskTree::new(&summary.included).collapse(depth));
+    }
+    print_json(&output)
+}
+
+/// listコマンドの処理
+fn handle_list(
+    tracker: &AppTracker,
+    period: String,
+    tags: &[String],
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let period: Period = period.parse()?;
+    let range = period.resolve(tracker.clock().today());
+    let entries = tracker.list_tagged_entries(range, tags)?;
+    if json {
+        return print_json(&PeriodEntries::new(range, tags, entries, tracker.clock().now()));
+    }
+
+    if entries.is_empty() {
+        println!("{}の作業記録はありません", period.label());
+        return Ok(());
+    }
+
+    if period == Period::Today {
+        println!("=== {}の作業記録 ===", period.label());
+    } else {
+        println!("=== {}の作業記録 ({}) ===", period.label(), range);
+    }
+    print_tag_filter(tags);
+
+    let now = tracker.clock().now();
+    for entry in &entries {
+        // 複数日の期間や日付をまたぐエントリは日付も表示する
+        let show_date = !range.is_single_day() || entry.start.date_naive() != range.start;
+        print_entry(entry, now, show_date);
+    }
+
+    Ok(())
+}
+
+/// exportコマンドの処理
+fn handle_export(
+    tracker: &AppTracker,
+    kind: ExportKind,
+    period: String,
+    per: Granularity,
+    tags: &[String],
+    exclude: &TaskFilter,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let period: Period = period.parse()?;
+    let range = period.resolve(tracker.clock().today());
+
+    match kind {
+        ExportKind::Entries => {
+            let entries = tracker.list_tagged_entries(range, tags)?;
+            export::write_entries_csv(out, &entries, tracker.clock().now())?;
+        }
+        ExportKind::Summary => {
+            let daily = tracker.get_tagged_daily_summary(range, tags)?;
+            let mut rows = export::aggregate(&daily, range, per);
+            rows.retain(|row| !exclude.matches(&row.task));
+            export::write_summary_csv(out, &rows)?;
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// export --format ics の処理
+fn handle_ics_export(
+    tracker: &AppTracker,
+    period: String,
+    tags: &[String],
+    calendar_id: &str,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let period: Period = period.parse()?;
+    let range = period.resolve(tracker.clock().today());
+    let entries = tracker.list_tagged_entries(range, tags)?;
+
+    ical::write_calendar(out, &entries, calendar_id, tracker.clock().now())?;
+    out.flush()?;
+    Ok(())
+}
+
+/// 出力先を開く（ファイルの指定がなければ標準出力）
+fn open_output(path: Option<&Path>, bom: bool) -> Result<Box<dyn Write>, Box<dyn std::error::Error>> {
+    let mut out: Box<dyn Write> = match path {
+        Some(path) => {
+            let file = File::create(path).map_err(|e| {
+                TimeCheckerError::DataSaveError(format!("{} の作成に失敗: {}", path.display(), e))
+            })?;
+            Box::new(io::BufWriter::new(file))
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+
+    if bom {
+        out.write_all(export::UTF8_BOM)?;
+    }
+    Ok(out)
+}
+
+/// importコマンドの処理
+fn handle_import(
+    tracker: &AppTracker,
+    calendar: ical::ParsedCalendar,
+    filter: &TaskFilter,
+    tags: Vec<String>,
+    dry_run: bool,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // --match の指定があれば件名が一致する予定だけ
+    let matches = |e: &ical::CalendarEvent| filter.is_empty() || filter.matches(&e.summary);
+    let candidates: Vec<TimeEntry> = calendar.events.iter().filter(|e| matches(e)).map(|e| e.to_entry(&tags)).collect();
+
+    let mut result = if dry_run {
+        tracker.preview_import(candidates)?
+    } else {
+        tracker.import_entries(candidates)?
+    };
+
+    // 時刻を正しく求められない予定も、取り込まなかった予定として表示する
+    result.skipped.extend(calendar.skipped.into_iter().filter(|s| matches(&s.event)).map(|s| SkippedEntry {
+        entry: s.event.to_entry(&tags),
+        reason: s.reason,
+    }));
+    result.skipped.sort_by_key(|s| s.entry.start);
+    if json {
+        return print_json(&result);
+    }
+
+    print_import_result(&result, tracker.clock().now(), dry_run);
+    Ok(())
+}
+
+/// インポートの結果を表示
+fn print_import_result(result: &ImportResult, now: DateTime<Local>, dry_run: bool) {
+    if result.added.is_empty() && result.skipped.is_empty() {
+        println!("取り込む予定はありません");
+        return;
+    }
+
+    if result.added.is_empty() {
+        println!("追加できる予定はありません");
+    } else if dry_run {
+        println!("{}件の予定を追加します（--dry-run のため保存していません）:", result.added.len());
+    } else {
+        println!("{}件の予定を追加しました:", result.added.len());
+    }
+    for entry in &result.added {
+        print_entry(entry, now, true);
+    }
+
+    if !result.skipped.is_empty() {
+        println!();
+        println!("{}件の予定をスキップしました:", result.skipped.len());
+        for skipped in &result.skipped {
+            let reason = match &skipped.reason {
+                SkipReason::Overlapping(id) => format!("ID {} のエントリと重複", id),
+                SkipReason::NotFinished => "まだ終わっていない".to_string(),
+                SkipReason::UnknownTimeZone(tzid) => format!("タイムゾーン {} の定義がない", tzid),
+                SkipReason::UnsupportedRecurrence(rule) => format!("対応していない繰り返しの規則 {}", rule),
+            };
+            println!(
+                "  {} - {} {} ({})",
+                skipped.entry.start.format("%Y-%m-%d %H:%M"),
+                skipped.entry.end.map(|e| e.format("%H:%M").to_string()).unwrap_or_default(),
+                skipped.entry.task,
+                reason
+            );
         }
-        _ => {
-            eprintln!("未対応の期間指定です: {}", period);
-            eprintln!("現在対応している期間: today");
-            Err("無効な期間指定".into())
+    }
+}
+
+/// editコマンドの処理
+#[allow(clippy::too_many_arguments)]
+fn handle_edit(
+    tracker: &AppTracker,
+    id: u64,
+    task: Option<String>,
+    note: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    tags: Option<String>,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let current = tracker
+        .store()
+        .get_entry(id)?
+        .ok_or(TimeCheckerError::EntryNotFound(id))?;
+
+    // 時刻だけの指定はエントリの開始日（開始時刻を変える場合は新しい開始日）を基準にする
+    let start = start
+        .map(|s| timespec::parse_datetime(&s, current.start.date_naive()))
+        .transpose()?;
+    let base_date = start.unwrap_or(current.start).date_naive();
+    let end = end.map(|s| timespec::parse_datetime(&s, base_date)).transpose()?;
+
+    // タグはカンマ区切り（空文字でタグを削除）
+    let tags = tags
+        .map(|t| t.split(',').filter(|t| !t.is_empty()).map(data::parse_tag).collect::<Result<Vec<_>, _>>())
+        .transpose()?;
+
+    let edit = EntryEdit {
+        task,
+        note: note.map(|n| if n.is_empty() { None } else { Some(n) }),
+        start,
+        end,
+        tags,
+    };
+
+    let updated = tracker.edit_entry(id, edit)?;
+    if json {
+        return print_json(&EntryDetail::new(updated, tracker.clock().now()));
+    }
+
+    println!("エントリを更新しました:");
+    print_entry(&updated, tracker.clock().now(), true);
+    Ok(())
+}
+
+/// deleteコマンドの処理
+fn handle_delete(tracker: &AppTracker, id: u64, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let removed = tracker.delete_entry(id)?;
+    if json {
+        return print_json(&EntryDetail::new(removed, tracker.clock().now()));
+    }
+
+    println!("エントリを削除しました:");
+    print_entry(&removed, tracker.clock().now(), true);
+    Ok(())
+}
+
+/// undoコマンドの処理
+fn handle_undo(tracker: &AppTracker, count: usize, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let operations = tracker.undo(count)?;
+    if json {
+        return print_json(&operations);
+    }
+    let now = tracker.clock().now();
+
+    for operation in &operations {
+        println!("取り消しました: {}", operation);
+        for change in operation.changes.iter().rev() {
+            print_change(change.after.as_ref(), change.before.as_ref(), now);
         }
     }
+    print_partial(operations.len(), count);
+    Ok(())
+}
+
+/// redoコマンドの処理
+fn handle_redo(tracker: &AppTracker, count: usize, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let operations = tracker.redo(count)?;
+    if json {
+        return print_json(&operations);
+    }
+    let now = tracker.clock().now();
+
+    for operation in &operations {
+        println!("やり直しました: {}", operation);
+        for change in &operation.changes {
+            print_change(change.before.as_ref(), change.after.as_ref(), now);
+        }
+    }
+    print_partial(operations.len(), count);
+    Ok(())
+}
+
+/// undo / redo で変わったエントリを表示
+fn print_change(from: Option<&TimeEntry>, to: Option<&TimeEntry>, now: DateTime<Local>) {
+    match (from, to) {
+        (Some(_), Some(entry)) => {
+            print!("  変更: ");
+            print_entry(entry, now, true);
+        }
+        (None, Some(entry)) => {
+            print!("  復元: ");
+            print_entry(entry, now, true);
+        }
+        (Some(entry), None) => {
+            print!("  削除: ");
+            print_entry(entry, now, true);
+        }
+        (None, None) => {}
+    }
+}
+
+/// 指定した数より少なく処理した場合に知らせる
+fn print_partial(done: usize, requested: usize) {
+    if done < requested {
+        println!("（{}件中{}件のみ処理しました）", requested, done);
+    }
+}
+
+/// migrateコマンドの処理
+fn handle_migrate(
+    location: &DataLocation,
+    from: StorageBackend,
+    to: StorageBackend,
+    file: Option<PathBuf>,
+    force: bool,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if from == to {
+        return Err(TimeCheckerError::ConfigError(format!(
+            "移行元（storage.backend）と移行先がどちらも {} です。--from で移行元を指定してください",
+            to.name()
+        ))
+        .into());
+    }
+
+    // --file は JSON の側に使う
+    let open = |backend: StorageBackend| -> Result<(Box<dyn Storage>, PathBuf), TimeCheckerError> {
+        match (backend, &file) {
+            (StorageBackend::Json, Some(file)) => Ok((Box::new(DataStore::new(file.clone())), file.clone())),
+            _ => Ok((storage::open(backend, location)?, location.path_for(backend))),
+        }
+    };
+    let (source, source_path) = open(from)?;
+    let (target, target_path) = open(to)?;
+
+    if !force && !target.load()?.is_empty() {
+        return Err(TimeCheckerError::DataSaveError(format!(
+            "移行先 {} にはすでにデータがあります。上書きする場合は --force を指定してください",
+            target_path.display()
+        ))
+        .into());
+    }
+
+    // 削除済みのエントリのIDを使い回さないよう、次に採番するIDも引き継ぐ
+    let entries = source.load_entries()?;
+    target.save_entries(&entries)?;
+
+    // 書き込んだ内容を読み戻して、移行元と完全に一致することを確認する
+    if target.load_entries()? != entries {
+        return Err(TimeCheckerError::DataSaveError(format!(
+            "{} に書き込んだ内容が移行元と一致しません",
+            target_path.display()
+        ))
+        .into());
+    }
+
+    if json {
+        return print_json(&serde_json::json!({
+            "entries": entries.len(),
+            "from": source_path,
+            "to": target_path,
+        }));
+    }
+
+    println!(
+        "{}件のエントリを {} から {} へ移行しました",
+        entries.len(),
+        source_path.display(),
+        target_path.display()
+    );
+    println!(
+        "移行先を使うには config.toml の [storage] に backend = \"{}\" を設定してください",
+        to.name()
+    );
+    Ok(())
+}
+
+/// completionコマンドの処理
+fn handle_completion(shell: Shell) -> Result<(), Box<dyn std::error::Error>> {
+    completion::generate(shell, &mut std::io::stdout())?;
+    Ok(())
+}
+
+/// 結果をJSONで標準出力に書き出す
+fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// 除外設定を適用した期間のサマリーをJSON出力用にまとめる
+fn period_summary(
+    tracker: &AppTracker,
+    period: Period,
+    exclude: &TaskFilter,
+) -> Result<PeriodSummary, TimeCheckerError> {
+    let range = period.resolve(tracker.clock().today());
+    let summary = tracker.get_filtered_summary(range, exclude)?;
+    Ok(PeriodSummary::new(range, &[], &summary))
+}
+
+/// エントリを1行（メモがあれば2行）で表示
+fn print_entry(entry: &TimeEntry, now: DateTime<Local>, show_date: bool) {
+    let start = if show_date {
+        entry.start.format("%Y-%m-%d %H:%M").to_string()
+    } else {
+        entry.start.format("%H:%M").to_string()
+    };
+    let end = match entry.end {
+        Some(end) if end.date_naive() != entry.start.date_naive() => end.format("%Y-%m-%d %H:%M").to_string(),
+        Some(end) => end.format("%H:%M").to_string(),
+        None => "進行中".to_string(),
+    };
+    let elapsed = entry.end.unwrap_or(now).signed_duration_since(entry.start);
+
+    println!(
+        "[{}] {} - {} ({}) {}{}",
+        entry.id,
+        start,
+        end,
+        format_duration(elapsed.to_std().unwrap_or_default()),
+        entry.task,
+        format_tags(&entry.tags)
+    );
+
+    if let Some(ref note) = entry.note {
+        println!("    メモ: {}", note);
+    }
 }
 
 /// サマリーを表示する共通関数
-fn display_summary(tracker: &Tracker) -> Result<(), Box<dyn std::error::Error>> {
-    let summary = tracker.get_today_summary()?;
+fn display_summary(
+    tracker: &AppTracker,
+    period: Period,
+    exclude: &TaskFilter,
+    tags: &[String],
+    tree: Option<usize>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let range = period.resolve(tracker.clock().today());
+    let summary = Summary::new(tracker.get_tagged_summary(range, tags)?, exclude);
 
     if summary.is_empty() {
-        println!("今日の作業記録はありません");
+        println!("{}の作業記録はありません", period.label());
+        return Ok(());
+    }
+
+    if period == Period::Today {
+        println!("=== {}の作業時間 ===", period.label());
+    } else {
+        println!("=== {}の作業時間 ({}) ===", period.label(), range);
+    }
+    print_tag_filter(tags);
+
+    // 階層表示では集計対象のタスクだけを木にする（除外されたタスクは従来どおり別枠）
+    match tree {
+        Some(depth) => print_tree(&TaskTree::new(&summary.included).collapse(depth).roots, 0),
+        None => print_tasks(&summary.included),
+    }
+    println!();
+
+    // 除外されたタスクがなければ従来どおり合計のみ表示
+    if summary.excluded.is_empty() {
+        println!("合計: {}", format_duration(summary.total()));
         return Ok(());
     }
 
-    println!("=== 今日の作業時間 ===");
+    println!("小計: {}", format_duration(summary.subtotal()));
+    println!();
+    println!("--- 除外されたタスク ---");
+    print_tasks(&summary.excluded);
+    println!();
+    println!("合計（除外含む）: {}", format_duration(summary.total()));
 
-    let mut tasks: Vec<_> = summary.iter().collect();
-    tasks.sort_by_key(|(name, _)| *name);
+    Ok(())
+}
 
-    let mut total_seconds = 0u64;
+/// サマリーをMarkdownのレポートとして表示（除外されたタスクは合計に含めず別に記載する）
+fn display_markdown_report(
+    tracker: &AppTracker,
+    period: Period,
+    exclude: &TaskFilter,
+    tags: &[String],
+    renderer: &MarkdownRenderer,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let range = period.resolve(tracker.clock().today());
+    let summary = Summary::new(tracker.get_tagged_summary(range, tags)?, exclude);
+    let entries = tracker.list_tagged_entries(range, tags)?;
 
-    for (task, duration) in tasks {
-        let seconds = duration.as_secs();
-        total_seconds += seconds;
+    let report = Report::new(period, range, &summary.included, &entries).with_excluded(&summary.excluded);
+    print!("{}", renderer.render(&report));
+
+    Ok(())
+}
 
-        let hours = seconds / 3600;
-        let minutes = (seconds % 3600) / 60;
+/// タグごとのサマリーを表示
+fn display_tag_summary(
+    tracker: &AppTracker,
+    period: Period,
+    exclude: &TaskFilter,
+    tags: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let range = period.resolve(tracker.clock().today());
+    let summary = tracker.get_tag_summary(range, tags, exclude)?;
 
-        println!("{}: {}時間{}分", task, hours, minutes);
+    if summary.is_empty() {
+        println!("{}の作業記録はありません", period.label());
+        return Ok(());
     }
 
-    println!();
+    if period == Period::Today {
+        println!("=== {}のタグ別作業時間 ===", period.label());
+    } else {
+        println!("=== {}のタグ別作業時間 ({}) ===", period.label(), range);
+    }
+    print_tag_filter(tags);
+
+    let mut by_tag: Vec<_> = summary.tags.iter().collect();
+    by_tag.sort_by_key(|(tag, _)| *tag);
+    for (tag, duration) in by_tag {
+        println!("+{}: {}", tag, format_duration(*duration));
+    }
+    if !summary.untagged.is_zero() {
+        println!("（タグなし）: {}", format_duration(summary.untagged));
+    }
+
+    // 除外されたタスクはタグごとには集計せず、通常の表示と同じく別枠で示す
+    if !summary.excluded.is_empty() {
+        println!();
+        println!("--- 除外されたタスク ---");
+        print_tasks(&summary.excluded);
+        println!("除外の合計: {}", format_duration(summary.excluded_total()));
+    }
 
-    let total_hours = total_seconds / 3600;
-    let total_minutes = (total_seconds % 3600) / 60;
-    println!("合計: {}時間{}分", total_hours, total_minutes);
+    // 複数のタグを持つエントリはそれぞれに集計されるので、合計は表示しない
+    println!();
+    println!("※複数のタグを持つエントリはそれぞれのタグに集計しています");
 
     Ok(())
 }
+
+/// タグで絞り込んでいる場合にその旨を表示
+fn print_tag_filter(tags: &[String]) {
+    if !tags.is_empty() {
+        println!("（タグ:{} のいずれかを持つエントリのみ）", format_tags(tags));
+    }
+}
+
+/// タグを「 +a +b」の形式にする（タグがなければ空文字）
+fn format_tags(tags: &[String]) -> String {
+    tags.iter().map(|tag| format!(" +{}", tag)).collect()
+}
+
+/// タスクの階層を字下げして小計つきで表示
+fn print_tree(nodes: &[TaskNode], level: usize) {
+    let indent = "  ".repeat(level);
+
+    for node in nodes {
+        println!("{}{}: {}", indent, node.name, format_duration(node.total));
+
+        // 子のある節にそのものの記録もある場合は、子と並べて別に表示する
+        if !node.children.is_empty() && !node.own.is_zero() {
+            println!("{}  （{} のみ）: {}", indent, node.name, format_duration(node.own));
+        }
+        print_tree(&node.children, level + 1);
+    }
+}
+
+/// タスクごとの作業時間を名前順に表示
+fn print_tasks(tasks: &HashMap<String, Duration>) {
+    let mut tasks: Vec<_> = tasks.iter().collect();
+    tasks.sort_by_key(|(name, _)| *name);
+
+    for (task, duration) in tasks {
+        println!("{}: {}", task, format_duration(*duration));
+    }
+}
+
+/// 作業時間を「X時間Y分」の形式にする
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+
+    format!("{}時間{}分", hours, minutes)
+}
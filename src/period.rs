@@ -0,0 +1,197 @@
+// 期間指定のパースと日付範囲の計算
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone};
+use std::fmt;
+use std::str::FromStr;
+use crate::error::TimeCheckerError;
+
+/// `show` コマンドなどで指定できる期間
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// 今日
+    Today,
+    /// 昨日
+    Yesterday,
+    /// 今週（月曜始まり）
+    Week,
+    /// 先週
+    LastWeek,
+    /// 今月
+    Month,
+    /// 先月
+    LastMonth,
+    /// 特定日（YYYY-MM-DD）
+    Date(NaiveDate),
+    /// 期間範囲（YYYY-MM-DD..YYYY-MM-DD、両端を含む）
+    Range(NaiveDate, NaiveDate),
+}
+
+/// 開始日と終了日（両端を含む）で表す日付範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    /// 開始日
+    pub start: NaiveDate,
+
+    /// 終了日（この日を含む）
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    /// 新しい日付範囲を作成
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+        Self { start, end }
+    }
+
+    /// 1日だけの範囲を作成
+    pub fn single(date: NaiveDate) -> Self {
+        Self::new(date, date)
+    }
+
+    /// 指定日が範囲に含まれるか
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// 範囲が1日だけかどうか
+    pub fn is_single_day(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// 範囲の境界となる時刻（開始日の0時〜終了日の翌日0時、終端は含まない）
+    pub fn bounds(&self) -> (DateTime<Local>, DateTime<Local>) {
+        let next_day = self.end.succ_opt().unwrap_or(self.end);
+        (start_of_day(self.start), start_of_day(next_day))
+    }
+
+    /// 範囲内の日付を順に列挙
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> {
+        let end = self.end;
+        self.start.iter_days().take_while(move |d| *d <= end)
+    }
+}
+
+/// 指定日のローカル時刻での0時
+/// 夏時間の切り替えで0時が存在しない場合はUTCとして解釈する
+pub fn start_of_day(date: NaiveDate) -> DateTime<Local> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .unwrap_or_else(|| Local.from_utc_datetime(&midnight))
+}
+
+impl fmt::Display for DateRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_single_day() {
+            write!(f, "{}", self.start.format("%Y-%m-%d"))
+        } else {
+            write!(f, "{} 〜 {}", self.start.format("%Y-%m-%d"), self.end.format("%Y-%m-%d"))
+        }
+    }
+}
+
+impl Period {
+    /// 基準日（通常は今日）から具体的な日付範囲を計算
+    pub fn resolve(&self, today: NaiveDate) -> DateRange {
+        match *self {
+            Period::Today => DateRange::single(today),
+            Period::Yesterday => {
+                let yesterday = today.pred_opt().unwrap_or(today);
+                DateRange::single(yesterday)
+            }
+            Period::Week => week_of(today),
+            Period::LastWeek => week_of(today - Duration::days(7)),
+            Period::Month => month_of(today),
+            Period::LastMonth => {
+                let first = first_day_of_month(today);
+                let last_of_prev = first.pred_opt().unwrap_or(first);
+                month_of(last_of_prev)
+            }
+            Period::Date(date) => DateRange::single(date),
+            Period::Range(start, end) => DateRange::new(start, end),
+        }
+    }
+
+    /// 見出しに使う期間の名前
+    pub fn label(&self) -> &'static str {
+        match self {
+            Period::Today => "今日",
+            Period::Yesterday => "昨日",
+            Period::Week => "今週",
+            Period::LastWeek => "先週",
+            Period::Month => "今月",
+            Period::LastMonth => "先月",
+            Period::Date(_) | Period::Range(_, _) => "指定期間",
+        }
+    }
+}
+
+impl FromStr for Period {
+    type Err = TimeCheckerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+
+        match trimmed {
+            "today" => return Ok(Period::Today),
+            "yesterday" => return Ok(Period::Yesterday),
+            "week" => return Ok(Period::Week),
+            "last-week" => return Ok(Period::LastWeek),
+            "month" => return Ok(Period::Month),
+            "last-month" => return Ok(Period::LastMonth),
+            _ => {}
+        }
+
+        // 期間範囲（YYYY-MM-DD..YYYY-MM-DD）
+        if let Some((from, to)) = trimmed.split_once("..") {
+            let start = parse_date(from).ok_or_else(|| TimeCheckerError::InvalidPeriod(s.to_string()))?;
+            let end = parse_date(to).ok_or_else(|| TimeCheckerError::InvalidPeriod(s.to_string()))?;
+
+            if end < start {
+                return Err(TimeCheckerError::InvalidPeriod(format!(
+                    "{}（終了日が開始日より前です）",
+                    s
+                )));
+            }
+
+            return Ok(Period::Range(start, end));
+        }
+
+        // 特定日（YYYY-MM-DD）
+        parse_date(trimmed)
+            .map(Period::Date)
+            .ok_or_else(|| TimeCheckerError::InvalidPeriod(s.to_string()))
+    }
+}
+
+/// YYYY-MM-DD 形式の日付をパース
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()
+}
+
+/// 指定日を含む週（月曜〜日曜）
+pub(crate) fn week_of(date: NaiveDate) -> DateRange {
+    let offset = date.weekday().num_days_from_monday() as i64;
+    let monday = date - Duration::days(offset);
+    DateRange::new(monday, monday + Duration::days(6))
+}
+
+/// 指定日の月の初日
+fn first_day_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// 指定日を含む月（1日〜末日）
+pub(crate) fn month_of(date: NaiveDate) -> DateRange {
+    let first = first_day_of_month(date);
+    let next_month_first = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    };
+    let last = next_month_first
+        .and_then(|d| d.pred_opt())
+        .unwrap_or(first);
+
+    DateRange::new(first, last)
+}
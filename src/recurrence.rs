@@ -0,0 +1,313 @@
+// 繰り返しの規則（RFC 5545 の RRULE）の展開
+// iCalendar の繰り返しの予定と、VTIMEZONE の標準時・夏時間の切り替わりに使う
+
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime, Weekday};
+use std::str::FromStr;
+
+/// 繰り返しの単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// 繰り返しの終わり（UNTIL）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    /// 日付のみ（その日の回まで）
+    Date(NaiveDate),
+    /// 予定と同じタイムゾーンの時刻
+    Local(NaiveDateTime),
+    /// UTC の時刻
+    Utc(NaiveDateTime),
+}
+
+/// 繰り返しの規則
+///
+/// 対応しているのは FREQ（DAILY / WEEKLY / MONTHLY / YEARLY）、INTERVAL、COUNT、UNTIL、
+/// BYDAY、BYMONTHDAY、BYMONTH、WKST。それ以外の指定（BYSETPOS など）はパースできない
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    /// 繰り返しの単位
+    pub frequency: Frequency,
+
+    /// 何単位ごとに繰り返すか（1以上）
+    pub interval: u32,
+
+    /// 回数（最初の回を含む）
+    pub count: Option<u32>,
+
+    /// 最後の回の日時
+    pub until: Option<Until>,
+
+    /// 曜日（何番目か、0 は毎週）
+    pub by_day: Vec<(i32, Weekday)>,
+
+    /// 日（負の数は月末から数える）
+    pub by_month_day: Vec<i32>,
+
+    /// 月
+    pub by_month: Vec<u32>,
+
+    /// 週の始まりの曜日
+    pub week_start: Weekday,
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    /// `FREQ=WEEKLY;BYDAY=MO,WE` 形式をパースする（エラーは理由）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut frequency = None;
+        let mut rule = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            week_start: Weekday::Mon,
+        };
+
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| format!("{} を解釈できません", part))?;
+            let invalid = || format!("{} を解釈できません", part);
+            let list = || value.split(',');
+
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(format!("FREQ={} には対応していません", value)),
+                    })
+                }
+                "INTERVAL" => rule.interval = value.parse().ok().filter(|&n| n > 0).ok_or_else(invalid)?,
+                "COUNT" => rule.count = Some(value.parse().map_err(|_| invalid())?),
+                "UNTIL" => rule.until = Some(parse_until(value).ok_or_else(invalid)?),
+                "BYDAY" => rule.by_day = list().map(parse_by_day).collect::<Option<_>>().ok_or_else(invalid)?,
+                "BYMONTHDAY" => {
+                    rule.by_month_day = list()
+                        .map(|d| d.parse().ok().filter(|d: &i32| (1..=31).contains(&d.abs())))
+                        .collect::<Option<_>>()
+                        .ok_or_else(invalid)?
+                }
+                "BYMONTH" => {
+                    rule.by_month = list()
+                        .map(|m| m.parse().ok().filter(|m| (1..=12).contains(m)))
+                        .collect::<Option<_>>()
+                        .ok_or_else(invalid)?
+                }
+                "WKST" => rule.week_start = parse_weekday(value).ok_or_else(invalid)?,
+                other => return Err(format!("{} には対応していません", other)),
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(|| "FREQ がありません".to_string())?;
+
+        // 何番目の曜日という指定は、月の中（MONTHLY、または BYMONTH のある YEARLY）でのみ扱う
+        let ordinal = rule.by_day.iter().any(|(n, _)| *n != 0);
+        let in_month = rule.frequency == Frequency::Monthly
+            || (rule.frequency == Frequency::Yearly && !rule.by_month.is_empty());
+        if ordinal && !in_month {
+            return Err("この FREQ での何番目の曜日の指定には対応していません".to_string());
+        }
+        if rule.frequency == Frequency::Weekly && !rule.by_month_day.is_empty() {
+            return Err("WEEKLY での BYMONTHDAY は指定できません".to_string());
+        }
+        let by_days = !rule.by_day.is_empty() || !rule.by_month_day.is_empty();
+        if rule.frequency == Frequency::Yearly && rule.by_month.is_empty() && by_days {
+            return Err("BYMONTH のない YEARLY での BYDAY / BYMONTHDAY には対応していません".to_string());
+        }
+
+        Ok(rule)
+    }
+}
+
+impl Recurrence {
+    /// `start` を最初の回として、`to` までの回の日時を順に列挙する
+    /// `to_utc` は UNTIL が UTC で指定されている場合に、回の日時を UTC にする関数
+    pub fn occurrences(
+        &self,
+        start: NaiveDateTime,
+        to: NaiveDateTime,
+        to_utc: impl Fn(NaiveDateTime) -> Option<NaiveDateTime>,
+    ) -> Vec<NaiveDateTime> {
+        let ended = |at: NaiveDateTime, count: u32| {
+            at > to
+                || self.count.is_some_and(|c| count >= c)
+                || match self.until {
+                    Some(Until::Date(date)) => at.date() > date,
+                    Some(Until::Local(until)) => at > until,
+                    Some(Until::Utc(until)) => to_utc(at).is_none_or(|utc| utc > until),
+                    None => false,
+                }
+        };
+
+        // 規則に合っていなくても最初の回は含める
+        if ended(start, 0) {
+            return Vec::new();
+        }
+        let mut result = vec![start];
+
+        for period in 0.. {
+            let Some((begin, dates)) = self.dates_in_period(start.date(), period) else {
+                break;
+            };
+            if begin > to.date() {
+                break;
+            }
+
+            for date in dates {
+                let at = date.and_time(start.time());
+                if at <= start {
+                    continue;
+                }
+                if ended(at, result.len() as u32) {
+                    return result;
+                }
+                result.push(at);
+            }
+        }
+
+        result
+    }
+
+    /// 最初の日から数えて `period` 番目の単位の始まりの日と、その中で規則に合う日（昇順）
+    fn dates_in_period(&self, first: NaiveDate, period: u32) -> Option<(NaiveDate, Vec<NaiveDate>)> {
+        let step = period.checked_mul(self.interval)?;
+
+        let (begin, mut dates) = match self.frequency {
+            Frequency::Daily => {
+                let date = first.checked_add_days(Days::new(step.into()))?;
+                (date, vec![date])
+            }
+            Frequency::Weekly => {
+                let offset = first.weekday().days_since(self.week_start);
+                let begin = first
+                    .checked_sub_days(Days::new(offset.into()))?
+                    .checked_add_days(Days::new(u64::from(step) * 7))?;
+                let dates = (0..7)
+                    .filter_map(|d| begin.checked_add_days(Days::new(d)))
+                    .filter(|date| match self.by_day.is_empty() {
+                        true => date.weekday() == first.weekday(),
+                        false => self.by_day.iter().any(|(_, wd)| *wd == date.weekday()),
+                    })
+                    .collect();
+                (begin, dates)
+            }
+            Frequency::Monthly => {
+                let begin = first.with_day(1)?.checked_add_months(Months::new(step))?;
+                (begin, self.days_in_month(begin, first.day()))
+            }
+            Frequency::Yearly => {
+                let year = first.year().checked_add(i32::try_from(step).ok()?)?;
+                let begin = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                let months = match self.by_month.is_empty() {
+                    true => vec![first.month()],
+                    false => self.by_month.clone(),
+                };
+                let dates = months
+                    .into_iter()
+                    .filter_map(|m| NaiveDate::from_ymd_opt(year, m, 1))
+                    .flat_map(|month| self.days_in_month(month, first.day()))
+                    .collect();
+                (begin, dates)
+            }
+        };
+
+        if !self.by_month.is_empty() {
+            dates.retain(|date| self.by_month.contains(&date.month()));
+        }
+        if self.frequency == Frequency::Daily {
+            dates.retain(|date| self.matches_month_day(date) && self.matches_weekday(date));
+        }
+        dates.sort();
+        Some((begin, dates))
+    }
+
+    /// 月の中で規則に合う日（BYMONTHDAY、BYDAY の指定がなければ `default_day` 日）
+    fn days_in_month(&self, month: NaiveDate, default_day: u32) -> Vec<NaiveDate> {
+        let last = last_day_of_month(month);
+        let days = (1..=last.day()).filter_map(|d| month.with_day(d));
+
+        if self.by_month_day.is_empty() && self.by_day.is_empty() {
+            return month.with_day(default_day).into_iter().collect();
+        }
+
+        days.filter(|date| self.matches_month_day(date))
+            .filter(|date| {
+                self.by_day.is_empty()
+                    || self.by_day.iter().any(|&(n, wd)| {
+                        let from_start = (date.day() as i32 - 1) / 7 + 1;
+                        let from_end = (last.day() as i32 - date.day() as i32) / 7 + 1;
+                        date.weekday() == wd && (n == 0 || n == from_start || -n == from_end)
+                    })
+            })
+            .collect()
+    }
+
+    /// BYMONTHDAY に合うか（指定がなければ常に true）
+    fn matches_month_day(&self, date: &NaiveDate) -> bool {
+        let last = last_day_of_month(*date).day() as i32;
+        self.by_month_day.is_empty()
+            || self.by_month_day.iter().any(|&d| {
+                let day = if d > 0 { d } else { last + d + 1 };
+                day == date.day() as i32
+            })
+    }
+
+    /// BYDAY の曜日に合うか（指定がなければ常に true）
+    fn matches_weekday(&self, date: &NaiveDate) -> bool {
+        self.by_day.is_empty() || self.by_day.iter().any(|(_, wd)| *wd == date.weekday())
+    }
+}
+
+/// 月の最終日
+fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(date)
+}
+
+/// `20251231`、`20251231T235959`、`20251231T235959Z` をパースする
+fn parse_until(value: &str) -> Option<Until> {
+    if let Some(utc) = value.strip_suffix(['Z', 'z']) {
+        return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok().map(Until::Utc);
+    }
+    if value.contains('T') {
+        return NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok().map(Until::Local);
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(Until::Date)
+}
+
+/// `MO`、`2SU`、`-1FR` をパースする
+fn parse_by_day(value: &str) -> Option<(i32, Weekday)> {
+    let split = value.len().checked_sub(2)?;
+    let (number, day) = value.split_at_checked(split)?;
+    let number = match number {
+        "" => 0,
+        n => n.strip_prefix('+').unwrap_or(n).parse().ok().filter(|n: &i32| (1..=5).contains(&n.abs()))?,
+    };
+    Some((number, parse_weekday(day)?))
+}
+
+/// `MO` 〜 `SU` をパースする
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value.to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
@@ -0,0 +1,254 @@
+// 日報などに貼り付けるためのMarkdown形式のレポート
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+use crate::config::ReportConfig;
+use crate::data::TimeEntry;
+use crate::period::{DateRange, Period};
+
+/// サマリーの出力形式
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// 端末向けのテキスト（デフォルト）
+    #[default]
+    Text,
+    /// Markdown
+    Markdown,
+}
+
+/// Markdownでのタスクの並べ方
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkdownStyle {
+    /// 表（デフォルト）
+    #[default]
+    Table,
+    /// 箇条書き
+    List,
+}
+
+/// 作業時間の書き方
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HourFormat {
+    /// 1時間30分（デフォルト）
+    #[default]
+    Japanese,
+    /// 1.50h
+    Decimal,
+    /// 1:30
+    Clock,
+}
+
+impl HourFormat {
+    /// 作業時間をこの書き方にする（秒は切り捨て）
+    pub fn format(&self, duration: Duration) -> String {
+        let minutes = duration.as_secs() / 60;
+        match self {
+            HourFormat::Japanese => format!("{}時間{}分", minutes / 60, minutes % 60),
+            HourFormat::Decimal => format!("{:.2}h", minutes as f64 / 60.0),
+            HourFormat::Clock => format!("{}:{:02}", minutes / 60, minutes % 60),
+        }
+    }
+}
+
+/// レポートの1行（タスクごと）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportRow {
+    /// タスク名
+    pub task: String,
+
+    /// 作業時間
+    pub duration: Duration,
+
+    /// エントリのメモ（重複を除いて開始時刻の順）
+    pub notes: Vec<String>,
+}
+
+/// タスクごとの集計結果とメモをまとめたレポート
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// 期間
+    pub period: Period,
+
+    /// 期間の日付範囲
+    pub range: DateRange,
+
+    /// タスクごとの行（タスク名の順）
+    pub rows: Vec<ReportRow>,
+
+    /// 除外されたタスクと作業時間（タスク名の順、合計には含めない）
+    pub excluded: Vec<(String, Duration)>,
+}
+
+impl Report {
+    /// 集計結果と期間中のエントリからレポートを作成（集計結果にないタスクのメモは使わない）
+    pub fn new(period: Period, range: DateRange, totals: &HashMap<String, Duration>, entries: &[TimeEntry]) -> Self {
+        let mut rows: Vec<ReportRow> = totals
+            .iter()
+            .map(|(task, duration)| ReportRow { task: task.clone(), duration: *duration, notes: Vec::new() })
+            .collect();
+        rows.sort_by(|a, b| a.task.cmp(&b.task));
+
+        let mut entries: Vec<&TimeEntry> = entries.iter().collect();
+        entries.sort_by_key(|e| e.start);
+        for entry in entries {
+            let Some(note) = entry.note.as_deref().map(str::trim).filter(|n| !n.is_empty()) else {
+                continue;
+            };
+            if let Some(row) = rows.iter_mut().find(|r| r.task == entry.task)
+                && !row.notes.iter().any(|n| n == note)
+            {
+                row.notes.push(note.to_string());
+            }
+        }
+
+        Self { period, range, rows, excluded: Vec::new() }
+    }
+
+    /// 除外されたタスクを設定（レポートに合計とは別に記載する）
+    pub fn with_excluded(mut self, excluded: &HashMap<String, Duration>) -> Self {
+        self.excluded = excluded.iter().map(|(task, duration)| (task.clone(), *duration)).collect();
+        self.excluded.sort();
+        self
+    }
+
+    /// 合計の作業時間
+    pub fn total(&self) -> Duration {
+        self.rows.iter().map(|r| r.duration).sum()
+    }
+}
+
+/// レポートをMarkdownにする
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownRenderer {
+    style: MarkdownStyle,
+    notes: bool,
+    heading: String,
+    heading_level: usize,
+    hours: HourFormat,
+}
+
+impl MarkdownRenderer {
+    /// `[report]` セクションの設定で作成
+    pub fn new(config: &ReportConfig) -> Self {
+        Self {
+            style: config.style,
+            notes: config.notes,
+            heading: config.heading.clone(),
+            heading_level: config.heading_level.clamp(1, 6) as usize,
+            hours: config.hours,
+        }
+    }
+
+    /// タスクの並べ方を設定
+    pub fn with_style(mut self, style: MarkdownStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// メモを含めるかどうかを設定
+    pub fn with_notes(mut self, notes: bool) -> Self {
+        self.notes = notes;
+        self
+    }
+
+    /// 見出し（`{period}` は期間の名前、`{range}` は日付範囲に置き換える）
+    pub fn heading(&self, report: &Report) -> String {
+        let text = self
+            .heading
+            .replace("{period}", report.period.label())
+            .replace("{range}", &report.range.to_string());
+        format!("{} {}", "#".repeat(self.heading_level), text.trim())
+    }
+
+    /// レポート全体を描画（末尾は改行）
+    pub fn render(&self, report: &Report) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{}", self.heading(report));
+        let _ = writeln!(out);
+
+        match (self.style, report.rows.is_empty()) {
+            (_, true) if report.excluded.is_empty() => {
+                let _ = writeln!(out, "作業記録はありません");
+            }
+            (_, true) => {
+                let _ = writeln!(out, "集計対象の作業記録はありません");
+            }
+            (MarkdownStyle::Table, false) => self.render_table(&mut out, report),
+            (MarkdownStyle::List, false) => self.render_list(&mut out, report),
+        }
+
+        // 除外したタスクがあれば、合計に含めていないことがわかるように書き添える
+        if !report.excluded.is_empty() {
+            let excluded: Vec<_> = report
+                .excluded
+                .iter()
+                .map(|(task, duration)| format!("{} {}", single_line(task), self.hours.format(*duration)))
+                .collect();
+            let _ = writeln!(out);
+            let _ = writeln!(out, "除外したタスク（合計に含まない）: {}", excluded.join("、"));
+        }
+        out
+    }
+
+    /// 表にする（メモは同じ行の列にまとめる）
+    fn render_table(&self, out: &mut String, report: &Report) {
+        if self.notes {
+            let _ = writeln!(out, "| タスク | 時間 | メモ |");
+            let _ = writeln!(out, "| --- | ---: | --- |");
+        } else {
+            let _ = writeln!(out, "| タスク | 時間 |");
+            let _ = writeln!(out, "| --- | ---: |");
+        }
+
+        for row in &report.rows {
+            let _ = write!(out, "| {} | {} |", escape_cell(&row.task), self.hours.format(row.duration));
+            if self.notes {
+                let notes: Vec<_> = row.notes.iter().map(|n| escape_cell(n)).collect();
+                let _ = write!(out, " {} |", notes.join(" / "));
+            }
+            let _ = writeln!(out);
+        }
+
+        let _ = write!(out, "| **合計** | **{}** |", self.hours.format(report.total()));
+        if self.notes {
+            let _ = write!(out, "  |");
+        }
+        let _ = writeln!(out);
+    }
+
+    /// 箇条書きにする（メモはタスクの下に字下げして並べる）
+    fn render_list(&self, out: &mut String, report: &Report) {
+        for row in &report.rows {
+            let _ = writeln!(out, "- {}: {}", single_line(&row.task), self.hours.format(row.duration));
+            if self.notes {
+                for note in &row.notes {
+                    let _ = writeln!(out, "  - {}", single_line(note));
+                }
+            }
+        }
+
+        let _ = writeln!(out);
+        let _ = writeln!(out, "**合計: {}**", self.hours.format(report.total()));
+    }
+}
+
+impl Default for MarkdownRenderer {
+    fn default() -> Self {
+        Self::new(&ReportConfig::default())
+    }
+}
+
+/// 改行を空白にして1行にする
+fn single_line(text: &str) -> String {
+    text.split(['\r', '\n']).filter(|s| !s.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+/// 表のセルで使えるようにする（`|` をエスケープして1行にする）
+fn escape_cell(text: &str) -> String {
+    single_line(text).replace('|', "\\|")
+}
@@ -0,0 +1,364 @@
+// SQLiteの保存先（`sqlite` 機能を有効にした場合のみ）
+// 期間の集計はインデックスを使って必要なエントリだけを読み込む
+
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use crate::data::{Entries, TimeEntry};
+use crate::error::TimeCheckerError;
+use crate::lock::DEFAULT_LOCK_TIMEOUT;
+use crate::period::DateRange;
+use crate::storage::Storage;
+
+/// テーブルとインデックスの定義
+/// 時刻は読み込み用のRFC 3339文字列と、範囲検索用のUNIX時刻（ナノ秒）の両方で持つ
+/// タグはJSONの配列として保存する（タグがなければ NULL）
+/// `meta` には次に採番するIDなどの、エントリ以外の情報を保存する
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS entries (
+        id       INTEGER PRIMARY KEY,
+        seq      INTEGER NOT NULL,
+        task     TEXT    NOT NULL,
+        start_at TEXT    NOT NULL,
+        end_at   TEXT,
+        note     TEXT,
+        start_ns INTEGER NOT NULL,
+        end_ns   INTEGER,
+        paused   INTEGER NOT NULL DEFAULT 0,
+        resumed_from INTEGER,
+        tags     TEXT
+    );
+    CREATE INDEX IF NOT EXISTS entries_start ON entries (start_ns);
+    CREATE INDEX IF NOT EXISTS entries_end ON entries (end_ns);
+    CREATE INDEX IF NOT EXISTS entries_seq ON entries (seq);
+    CREATE TABLE IF NOT EXISTS meta (
+        name  TEXT    PRIMARY KEY,
+        value INTEGER NOT NULL
+    );
+";
+
+/// 次に採番するIDを保存する `meta` の行の名前（削除したエントリのIDを使い回さないため）
+const NEXT_ID_KEY: &str = "next_id";
+
+/// 古いデータベースに追加するカラム（一時停止の情報、タグ）
+const ADDED_COLUMNS: [(&str, &str); 3] = [
+    ("paused", "INTEGER NOT NULL DEFAULT 0"),
+    ("resumed_from", "INTEGER"),
+    ("tags", "TEXT"),
+];
+
+/// 読み込むカラム（`row_to_entry` の順序と合わせる）
+const COLUMNS: &str = "id, task, start_at, end_at, note, paused, resumed_from, tags";
+
+/// SQLiteのデータベースにエントリを保存する保存先
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+    path: PathBuf,
+}
+
+impl SqliteStore {
+    /// データベースを開く（なければ作成する）
+    pub fn open(path: &Path) -> Result<Self, TimeCheckerError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                TimeCheckerError::DataLoadError(format!("ディレクトリの作成に失敗: {}", e))
+            })?;
+        }
+
+        let conn = Connection::open(path).map_err(load_error)?;
+
+        // 他のプロセスが書き込み中の場合は JSON の保存先と同じ時間だけ待つ
+        conn.busy_timeout(DEFAULT_LOCK_TIMEOUT).map_err(load_error)?;
+        conn.execute_batch(SCHEMA).map_err(load_error)?;
+        add_missing_columns(&conn)?;
+
+        Ok(Self { conn: Mutex::new(conn), path: path.to_path_buf() })
+    }
+
+    /// データベースファイルのパスを取得
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 条件に一致するエントリを保存順に読み込む
+    fn query(
+        conn: &Connection,
+        condition: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        let sql = format!("SELECT {} FROM entries {} ORDER BY seq", COLUMNS, condition);
+        let mut stmt = conn.prepare(&sql).map_err(load_error)?;
+        let rows = stmt.query_map(params, row_to_entry).map_err(load_error)?;
+
+        rows.map(|row| row.map_err(load_error).and_then(|entry| entry)).collect()
+    }
+
+    /// すべてのエントリを次に採番するIDとともに読み込む
+    fn query_entries(conn: &Connection) -> Result<Entries, TimeCheckerError> {
+        let entries = Self::query(conn, "", [])?;
+        let next_id: Option<i64> = conn
+            .query_row("SELECT value FROM meta WHERE name = ?1", params![NEXT_ID_KEY], |row| row.get(0))
+            .optional()
+            .map_err(load_error)?;
+
+        Ok(Entries::new(entries, next_id.unwrap_or(0) as u64))
+    }
+
+    /// 次に採番するIDを保存
+    fn write_next_id(conn: &Connection, next_id: u64) -> Result<(), TimeCheckerError> {
+        conn.execute(
+            "INSERT INTO meta (name, value) VALUES (?1, ?2) ON CONFLICT (name) DO UPDATE SET value = excluded.value",
+            params![NEXT_ID_KEY, to_sql_id(next_id)?],
+        )
+        .map_err(save_error)?;
+
+        Ok(())
+    }
+
+    /// 変更前と変更後のエントリを比べ、変わった行だけを書き込む
+    fn write_changes(conn: &Connection, original: &Entries, entries: &Entries) -> Result<(), TimeCheckerError> {
+        let before: HashMap<u64, &TimeEntry> = original.iter().map(|e| (e.id, e)).collect();
+        let after: HashMap<u64, &TimeEntry> = entries.iter().map(|e| (e.id, e)).collect();
+
+        for id in before.keys().filter(|id| !after.contains_key(id)) {
+            conn.execute("DELETE FROM entries WHERE id = ?1", params![to_sql_id(*id)?])
+                .map_err(save_error)?;
+        }
+
+        // 新しいエントリは末尾に、変更されたエントリは元の位置のまま保存する
+        let mut next_seq: i64 = conn
+            .query_row("SELECT COALESCE(MAX(seq), -1) + 1 FROM entries", [], |row| row.get(0))
+            .map_err(save_error)?;
+
+        for entry in entries.iter() {
+            match before.get(&entry.id) {
+                Some(old) if *old == entry => {}
+                Some(_) => {
+                    conn.execute(
+                        "UPDATE entries SET task = ?2, start_at = ?3, end_at = ?4, note = ?5, start_ns = ?6, end_ns = ?7,
+                         paused = ?8, resumed_from = ?9, tags = ?10
+                         WHERE id = ?1",
+                        params![
+                            to_sql_id(entry.id)?,
+                            entry.task,
+                            entry.start.to_rfc3339(),
+                            entry.end.map(|end| end.to_rfc3339()),
+                            entry.note,
+                            to_nanos(entry.start),
+                            entry.end.map(to_nanos),
+                            entry.paused,
+                            entry.resumed_from.map(to_sql_id).transpose()?,
+                            tags_to_sql(&entry.tags)?,
+                        ],
+                    )
+                    .map_err(save_error)?;
+                }
+                None => {
+                    insert_entry(conn, entry, next_seq)?;
+                    next_seq += 1;
+                }
+            }
+        }
+
+        if entries.next_id() != original.next_id() {
+            Self::write_next_id(conn, entries.next_id())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Storage for SqliteStore {
+    fn load_entries(&self) -> Result<Entries, TimeCheckerError> {
+        Self::query_entries(&self.conn())
+    }
+
+    /// すべてのエントリを入れ替える（`migrate` での取り込みに使う）
+    fn save_entries(&self, entries: &Entries) -> Result<(), TimeCheckerError> {
+        let mut entries = entries.clone();
+        entries.assign_ids();
+
+        let conn = self.conn();
+        let tx = conn.unchecked_transaction().map_err(save_error)?;
+
+        tx.execute("DELETE FROM entries", []).map_err(save_error)?;
+        for (seq, entry) in (0..).zip(entries.iter()) {
+            insert_entry(&tx, entry, seq)?;
+        }
+        Self::write_next_id(&tx, entries.next_id())?;
+
+        tx.commit().map_err(save_error)
+    }
+
+    fn load(&self) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        Self::query(&self.conn(), "", [])
+    }
+
+    /// 書き込みロックを取ってから 読み込み → 変更 → 差分の書き込み を行う
+    fn update(
+        &self,
+        update: &mut dyn FnMut(&mut Entries) -> Result<(), TimeCheckerError>,
+    ) -> Result<(), TimeCheckerError> {
+        let conn = self.conn();
+
+        // IMMEDIATE で開始して、読み込んだ時点から他のプロセスの書き込みを止める
+        conn.execute_batch("BEGIN IMMEDIATE").map_err(save_error)?;
+
+        let result = (|| {
+            let original = Self::query_entries(&conn)?;
+            let mut entries = original.clone();
+            update(&mut entries)?;
+            entries.assign_ids();
+            Self::write_changes(&conn, &original, &entries)
+        })();
+
+        match result {
+            Ok(()) => conn.execute_batch("COMMIT").map_err(save_error),
+            Err(e) => {
+                let _ = conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    fn get_current_task(&self) -> Result<Option<TimeEntry>, TimeCheckerError> {
+        let sql = format!("SELECT {} FROM entries WHERE end_ns IS NULL ORDER BY seq DESC LIMIT 1", COLUMNS);
+        let conn = self.conn();
+        let entry = conn.query_row(&sql, [], row_to_entry).optional().map_err(load_error)?;
+        entry.transpose()
+    }
+
+    fn get_entry(&self, id: u64) -> Result<Option<TimeEntry>, TimeCheckerError> {
+        let sql = format!("SELECT {} FROM entries WHERE id = ?1", COLUMNS);
+        let conn = self.conn();
+        let entry = conn
+            .query_row(&sql, params![to_sql_id(id)?], row_to_entry)
+            .optional()
+            .map_err(load_error)?;
+        entry.transpose()
+    }
+
+    fn get_entries_in_range(&self, range: DateRange) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        let (from, to) = range.bounds();
+        Self::query(
+            &self.conn(),
+            "WHERE start_ns < ?2 AND (end_ns IS NULL OR end_ns > ?1)",
+            params![to_nanos(from), to_nanos(to)],
+        )
+    }
+}
+
+/// 古いバージョンで作成したデータベースに足りないカラムを追加
+fn add_missing_columns(conn: &Connection) -> Result<(), TimeCheckerError> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('entries')").map_err(load_error)?;
+    let existing = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(load_error)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(load_error)?;
+
+    for (name, definition) in ADDED_COLUMNS {
+        if !existing.iter().any(|column| column == name) {
+            conn.execute_batch(&format!("ALTER TABLE entries ADD COLUMN {} {}", name, definition))
+                .map_err(load_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 1件のエントリを挿入
+fn insert_entry(conn: &Connection, entry: &TimeEntry, seq: i64) -> Result<(), TimeCheckerError> {
+    conn.execute(
+        "INSERT INTO entries (id, seq, task, start_at, end_at, note, start_ns, end_ns, paused, resumed_from, tags)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            to_sql_id(entry.id)?,
+            seq,
+            entry.task,
+            entry.start.to_rfc3339(),
+            entry.end.map(|end| end.to_rfc3339()),
+            entry.note,
+            to_nanos(entry.start),
+            entry.end.map(to_nanos),
+            entry.paused,
+            entry.resumed_from.map(to_sql_id).transpose()?,
+            tags_to_sql(&entry.tags)?,
+        ],
+    )
+    .map_err(save_error)?;
+
+    Ok(())
+}
+
+/// 行をエントリに変換（時刻のパースに失敗した場合は内側の Result でエラーを返す）
+fn row_to_entry(row: &Row) -> rusqlite::Result<Result<TimeEntry, TimeCheckerError>> {
+    let id: i64 = row.get(0)?;
+    let task: String = row.get(1)?;
+    let start: String = row.get(2)?;
+    let end: Option<String> = row.get(3)?;
+    let note: Option<String> = row.get(4)?;
+    let paused: bool = row.get(5)?;
+    let resumed_from: Option<i64> = row.get(6)?;
+    let tags: Option<String> = row.get(7)?;
+
+    Ok((|| {
+        Ok(TimeEntry {
+            id: id as u64,
+            task,
+            start: parse_time(&start)?,
+            end: end.as_deref().map(parse_time).transpose()?,
+            note,
+            paused,
+            resumed_from: resumed_from.map(|id| id as u64),
+            tags: tags.as_deref().map(tags_from_sql).transpose()?.unwrap_or_default(),
+        })
+    })())
+}
+
+/// 保存されているRFC 3339の時刻をパース
+fn parse_time(s: &str) -> Result<DateTime<Local>, TimeCheckerError> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Local))
+        .map_err(|e| TimeCheckerError::DataLoadError(format!("時刻 {} のパースに失敗: {}", s, e)))
+}
+
+/// タグをJSONの配列に変換（タグがなければ NULL）
+fn tags_to_sql(tags: &[String]) -> Result<Option<String>, TimeCheckerError> {
+    if tags.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(tags)
+        .map(Some)
+        .map_err(|e| TimeCheckerError::DataSaveError(format!("タグのシリアライズに失敗: {}", e)))
+}
+
+/// 保存されているJSONの配列をタグに変換
+fn tags_from_sql(s: &str) -> Result<Vec<String>, TimeCheckerError> {
+    serde_json::from_str(s).map_err(|e| TimeCheckerError::DataLoadError(format!("タグ {} のパースに失敗: {}", s, e)))
+}
+
+/// 範囲検索用のUNIX時刻（ナノ秒）
+fn to_nanos(time: DateTime<Local>) -> i64 {
+    time.timestamp_nanos_opt().unwrap_or(i64::MAX)
+}
+
+/// SQLiteの整数型（符号付き64ビット）に収まるIDに変換
+fn to_sql_id(id: u64) -> Result<i64, TimeCheckerError> {
+    i64::try_from(id).map_err(|_| TimeCheckerError::DataSaveError(format!("ID {} が大きすぎます", id)))
+}
+
+fn load_error(e: rusqlite::Error) -> TimeCheckerError {
+    TimeCheckerError::DataLoadError(format!("データベースの読み込みに失敗: {}", e))
+}
+
+fn save_error(e: rusqlite::Error) -> TimeCheckerError {
+    TimeCheckerError::DataSaveError(format!("データベースの書き込みに失敗: {}", e))
+}
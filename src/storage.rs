@@ -0,0 +1,208 @@
+// データの保存先を差し替えるためのトレイトとメモリ上の実装
+
+use std::sync::{Mutex, MutexGuard};
+use crate::config::{DataLocation, StorageBackend};
+use crate::data::{DataStore, Entries, TimeEntry};
+use crate::error::TimeCheckerError;
+use crate::journal::JournalStore;
+use crate::period::DateRange;
+
+/// 設定された保存形式で保存先を開く
+pub fn open(backend: StorageBackend, location: &DataLocation) -> Result<Box<dyn Storage>, TimeCheckerError> {
+    match backend {
+        StorageBackend::Json => Ok(Box::new(DataStore::new(location.data_file()))),
+        #[cfg(feature = "sqlite")]
+        StorageBackend::Sqlite => Ok(Box::new(crate::sqlite::SqliteStore::open(&location.database_file())?)),
+        #[cfg(not(feature = "sqlite"))]
+        StorageBackend::Sqlite => Err(sqlite_unavailable()),
+        StorageBackend::Journal => Ok(Box::new(JournalStore::new(location.dir.clone()))),
+    }
+}
+
+/// `sqlite` 機能なしでビルドされている場合のエラー
+#[cfg(not(feature = "sqlite"))]
+pub fn sqlite_unavailable() -> TimeCheckerError {
+    TimeCheckerError::ConfigError(
+        "SQLiteの保存先を使うには `--features sqlite` を付けてビルドしてください".to_string(),
+    )
+}
+
+/// エントリの保存先
+/// `DataStore`（JSONファイル）、`JournalStore`（ジャーナル）、`MemoryStorage`（メモリ上）、
+/// `SqliteStore`（`sqlite` 機能）が実装している
+pub trait Storage {
+    /// すべてのエントリを次に採番するIDとともに読み込む
+    fn load_entries(&self) -> Result<Entries, TimeCheckerError>;
+
+    /// すべてのエントリを次に採番するIDとともに保存する（IDが未採番のエントリには採番する）
+    fn save_entries(&self, entries: &Entries) -> Result<(), TimeCheckerError>;
+
+    /// 他の更新と混ざらないように 読み込み → 変更 → 保存 を行う
+    /// `update` がエラーを返した場合は保存しない
+    /// 実装は `update` をちょうど1回呼び出すか、エラーを返さなければならない
+    fn update(
+        &self,
+        update: &mut dyn FnMut(&mut Entries) -> Result<(), TimeCheckerError>,
+    ) -> Result<(), TimeCheckerError>;
+
+    /// すべてのエントリを読み込む
+    fn load(&self) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        Ok(self.load_entries()?.into_vec())
+    }
+
+    /// すべてのエントリを保存する（次に採番するIDはエントリの最大のID + 1）
+    fn save(&self, entries: &[TimeEntry]) -> Result<(), TimeCheckerError> {
+        self.save_entries(&Entries::from(entries.to_vec()))
+    }
+
+    /// `update` の結果を値として受け取れる版
+    fn transaction<T, F>(&self, update: F) -> Result<T, TimeCheckerError>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Entries) -> Result<T, TimeCheckerError>,
+    {
+        let mut update = Some(update);
+        let mut result = None;
+
+        self.update(&mut |entries| {
+            if let Some(update) = update.take() {
+                result = Some(update(entries)?);
+            }
+            Ok(())
+        })?;
+
+        result.ok_or_else(|| TimeCheckerError::DataSaveError("更新処理が実行されませんでした".to_string()))
+    }
+
+    /// 現在進行中のタスクを取得（end が None のもの）
+    fn get_current_task(&self) -> Result<Option<TimeEntry>, TimeCheckerError> {
+        let entries = self.load()?;
+
+        // 最後の end が None のエントリを探す
+        Ok(entries.into_iter().rev().find(|e| e.end.is_none()))
+    }
+
+    /// IDを指定してエントリを取得
+    fn get_entry(&self, id: u64) -> Result<Option<TimeEntry>, TimeCheckerError> {
+        let entries = self.load()?;
+        Ok(entries.into_iter().find(|e| e.id == id))
+    }
+
+    /// 指定した日付範囲にかかっているエントリを取得
+    /// 日付をまたぐエントリは範囲と少しでも重なっていれば含まれる
+    fn get_entries_in_range(&self, range: DateRange) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        let entries = self.load()?;
+        let (from, to) = range.bounds();
+
+        Ok(entries
+            .into_iter()
+            .filter(|e| e.overlaps(from, to))
+            .collect())
+    }
+
+    /// エントリを末尾に追加し、採番後のエントリを返す（重なりの検証はしない）
+    fn append(&self, entry: TimeEntry) -> Result<TimeEntry, TimeCheckerError>
+    where
+        Self: Sized,
+    {
+        self.transaction(|entries| {
+            let mut entry = entry;
+            if entry.id == 0 {
+                entry.id = entries.next_id();
+            }
+            entries.push(entry.clone());
+            Ok(entry)
+        })
+    }
+}
+
+/// 実行時に保存先を選ぶ場合のためのボックス化したトレイトオブジェクト
+impl Storage for Box<dyn Storage> {
+    fn load_entries(&self) -> Result<Entries, TimeCheckerError> {
+        (**self).load_entries()
+    }
+
+    fn save_entries(&self, entries: &Entries) -> Result<(), TimeCheckerError> {
+        (**self).save_entries(entries)
+    }
+
+    fn update(
+        &self,
+        update: &mut dyn FnMut(&mut Entries) -> Result<(), TimeCheckerError>,
+    ) -> Result<(), TimeCheckerError> {
+        (**self).update(update)
+    }
+
+    fn load(&self) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        (**self).load()
+    }
+
+    fn save(&self, entries: &[TimeEntry]) -> Result<(), TimeCheckerError> {
+        (**self).save(entries)
+    }
+
+    fn get_current_task(&self) -> Result<Option<TimeEntry>, TimeCheckerError> {
+        (**self).get_current_task()
+    }
+
+    fn get_entry(&self, id: u64) -> Result<Option<TimeEntry>, TimeCheckerError> {
+        (**self).get_entry(id)
+    }
+
+    fn get_entries_in_range(&self, range: DateRange) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        (**self).get_entries_in_range(range)
+    }
+}
+
+/// メモリ上にエントリを保持する保存先（テストやライブラリへの組み込み用）
+/// ファイルには一切書き込まない
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    entries: Mutex<Entries>,
+}
+
+impl MemoryStorage {
+    /// 空の保存先を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 指定したエントリを持つ保存先を作成（IDが未採番のエントリには採番する）
+    pub fn with_entries(entries: Vec<TimeEntry>) -> Self {
+        let mut entries = Entries::from(entries);
+        entries.assign_ids();
+        Self { entries: Mutex::new(entries) }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        // 更新は丸ごと置き換えるので、他スレッドのパニックで汚染されていても中身は整合している
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load_entries(&self) -> Result<Entries, TimeCheckerError> {
+        Ok(self.lock().clone())
+    }
+
+    fn save_entries(&self, entries: &Entries) -> Result<(), TimeCheckerError> {
+        let mut entries = entries.clone();
+        entries.assign_ids();
+        *self.lock() = entries;
+        Ok(())
+    }
+
+    fn update(
+        &self,
+        update: &mut dyn FnMut(&mut Entries) -> Result<(), TimeCheckerError>,
+    ) -> Result<(), TimeCheckerError> {
+        let mut stored = self.lock();
+
+        let mut entries = stored.clone();
+        update(&mut entries)?;
+        entries.assign_ids();
+        *stored = entries;
+
+        Ok(())
+    }
+}
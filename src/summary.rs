@@ -0,0 +1,400 @@
+// サマリー（集計結果）の型
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::data::TimeEntry;
+use crate::filter::TaskFilter;
+use crate::period::DateRange;
+
+/// 除外設定を適用したタスクごとの集計結果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    /// 集計対象のタスク
+    pub included: HashMap<String, Duration>,
+
+    /// 除外されたタスク（透明性のために別枠で保持する）
+    pub excluded: HashMap<String, Duration>,
+}
+
+impl Summary {
+    /// タスクごとの集計結果を除外フィルタで振り分ける
+    pub fn new(totals: HashMap<String, Duration>, exclude: &TaskFilter) -> Self {
+        let (excluded, included) = totals
+            .into_iter()
+            .partition(|(task, _)| exclude.matches(task));
+
+        Self { included, excluded }
+    }
+
+    /// 集計対象のタスクの合計（小計）
+    pub fn subtotal(&self) -> Duration {
+        self.included.values().sum()
+    }
+
+    /// 除外されたタスクの合計
+    pub fn excluded_total(&self) -> Duration {
+        self.excluded.values().sum()
+    }
+
+    /// 除外されたタスクも含めた合計
+    pub fn total(&self) -> Duration {
+        self.subtotal() + self.excluded_total()
+    }
+
+    /// 記録が1件もないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.included.is_empty() && self.excluded.is_empty()
+    }
+}
+
+/// タグごとの集計結果
+/// 複数のタグを持つエントリはそれぞれのタグに集計するため、タグごとの合計はエントリの合計と一致しない
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagSummary {
+    /// タグごとの作業時間
+    pub tags: HashMap<String, Duration>,
+
+    /// タグのないエントリの作業時間
+    pub untagged: Duration,
+
+    /// 除外されたタスク（タグごとには集計せず、タスクごとに別枠で保持する）
+    pub excluded: HashMap<String, Duration>,
+}
+
+impl TagSummary {
+    /// 除外されたタスクの合計
+    pub fn excluded_total(&self) -> Duration {
+        self.excluded.values().sum()
+    }
+
+    /// 記録が1件もないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.untagged.is_zero() && self.excluded.is_empty()
+    }
+}
+
+/// タスク名の階層の区切り（`clientA/設計/レビュー`）
+pub const TASK_PATH_SEPARATOR: char = '/';
+
+/// タスク名を階層ごとに分割する（前後や連続した区切りは無視する）
+pub fn task_path(task: &str) -> Vec<&str> {
+    task.split(TASK_PATH_SEPARATOR)
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// タスクの階層の1つの節
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct TaskNode {
+    /// この階層の名前（`clientA/設計` の `設計`）
+    pub name: String,
+
+    /// この節以下のすべてのタスクの合計（小計）
+    #[serde(rename = "total_seconds", serialize_with = "as_seconds")]
+    pub total: Duration,
+
+    /// この節そのものに記録された作業時間（`clientA/設計` の子でなく `clientA/設計` 自体）
+    #[serde(rename = "own_seconds", serialize_with = "as_seconds")]
+    pub own: Duration,
+
+    /// 子の節（名前順）
+    pub children: Vec<TaskNode>,
+}
+
+impl TaskNode {
+    fn new(name: &str) -> Self {
+        Self { name: name.to_string(), ..Default::default() }
+    }
+
+    /// パスの残りをたどって作業時間を加算する
+    fn add(&mut self, path: &[&str], duration: Duration) {
+        self.total += duration;
+
+        let Some((first, rest)) = path.split_first() else {
+            self.own += duration;
+            return;
+        };
+
+        let index = match self.children.binary_search_by(|child| child.name.as_str().cmp(first)) {
+            Ok(index) => index,
+            Err(index) => {
+                self.children.insert(index, TaskNode::new(first));
+                index
+            }
+        };
+        self.children[index].add(rest, duration);
+    }
+
+    /// 指定した深さより下の節を畳み、その時間を親の `own` にまとめる
+    fn collapse(&mut self, depth: usize) {
+        if depth <= 1 {
+            self.children.clear();
+            self.own = self.total;
+            return;
+        }
+        for child in &mut self.children {
+            child.collapse(depth - 1);
+        }
+    }
+}
+
+/// `/` で区切ったタスク名を階層として集計した木
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskTree {
+    /// 最上位の節（名前順）
+    pub roots: Vec<TaskNode>,
+}
+
+impl TaskTree {
+    /// タスクごとの集計結果から木を作る
+    pub fn new(totals: &HashMap<String, Duration>) -> Self {
+        let mut root = TaskNode::default();
+        for (task, duration) in totals {
+            let path = task_path(task);
+            // 区切りしかないタスク名はそのままの名前で扱う
+            let path = if path.is_empty() { vec![task.as_str()] } else { path };
+            root.add(&path, *duration);
+        }
+
+        Self { roots: root.children }
+    }
+
+    /// 指定した深さ（1 なら最上位のみ）より下の階層を畳む
+    pub fn collapse(mut self, depth: usize) -> Self {
+        for root in &mut self.roots {
+            root.collapse(depth);
+        }
+        self
+    }
+
+    /// すべてのタスクの合計
+    pub fn total(&self) -> Duration {
+        self.roots.iter().map(|root| root.total).sum()
+    }
+}
+
+/// タスクごとの作業時間（JSONでは秒数）
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TaskTotal {
+    /// タスク名
+    pub task: String,
+
+    /// 作業時間
+    #[serde(rename = "seconds", serialize_with = "as_seconds")]
+    pub duration: Duration,
+}
+
+/// タグごとの作業時間（JSONでは秒数）
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TagTotal {
+    /// タグ名
+    pub tag: String,
+
+    /// 作業時間
+    #[serde(rename = "seconds", serialize_with = "as_seconds")]
+    pub duration: Duration,
+}
+
+/// 期間を含めたタスクごとの集計結果（`--json` での出力用）
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeriodSummary {
+    /// 期間の開始日
+    pub start: NaiveDate,
+
+    /// 期間の終了日（この日を含む）
+    pub end: NaiveDate,
+
+    /// 絞り込みに使ったタグ
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// 集計対象のタスク（名前順）
+    pub tasks: Vec<TaskTotal>,
+
+    /// 除外されたタスク（名前順）
+    pub excluded: Vec<TaskTotal>,
+
+    /// 集計対象のタスクの合計
+    #[serde(rename = "subtotal_seconds", serialize_with = "as_seconds")]
+    pub subtotal: Duration,
+
+    /// 除外されたタスクも含めた合計
+    #[serde(rename = "total_seconds", serialize_with = "as_seconds")]
+    pub total: Duration,
+
+    /// タスクの階層（`--tree` を指定した場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tree: Option<Vec<TaskNode>>,
+}
+
+impl PeriodSummary {
+    /// 期間と絞り込みのタグを添えて集計結果をまとめる
+    pub fn new(range: DateRange, tags: &[String], summary: &Summary) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+            tags: tags.to_vec(),
+            tasks: task_totals(&summary.included),
+            excluded: task_totals(&summary.excluded),
+            subtotal: summary.subtotal(),
+            total: summary.total(),
+            tree: None,
+        }
+    }
+
+    /// タスクの階層を含める
+    pub fn with_tree(mut self, tree: TaskTree) -> Self {
+        self.tree = Some(tree.roots);
+        self
+    }
+}
+
+/// 期間を含めたタグごとの集計結果（`--json` での出力用）
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeriodTagSummary {
+    /// 期間の開始日
+    pub start: NaiveDate,
+
+    /// 期間の終了日（この日を含む）
+    pub end: NaiveDate,
+
+    /// 絞り込みに使ったタグ
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub filter: Vec<String>,
+
+    /// タグごとの作業時間（名前順）
+    pub tags: Vec<TagTotal>,
+
+    /// タグのないエントリの作業時間
+    #[serde(rename = "untagged_seconds", serialize_with = "as_seconds")]
+    pub untagged: Duration,
+
+    /// 除外されたタスク（名前順）
+    pub excluded: Vec<TaskTotal>,
+}
+
+impl PeriodTagSummary {
+    /// 期間と絞り込みのタグを添えてタグごとの集計結果をまとめる
+    pub fn new(range: DateRange, filter: &[String], summary: &TagSummary) -> Self {
+        let tags = task_totals(&summary.tags)
+            .into_iter()
+            .map(|t| TagTotal { tag: t.task, duration: t.duration })
+            .collect();
+        Self {
+            start: range.start,
+            end: range.end,
+            filter: filter.to_vec(),
+            tags,
+            untagged: summary.untagged,
+            excluded: task_totals(&summary.excluded),
+        }
+    }
+}
+
+/// 経過時間を添えたエントリ（`--json` での出力用）
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EntryDetail {
+    /// エントリ
+    #[serde(flatten)]
+    pub entry: TimeEntry,
+
+    /// 開始から終了（進行中なら現在）までの時間
+    #[serde(rename = "elapsed_seconds", serialize_with = "as_seconds")]
+    pub elapsed: Duration,
+
+    /// 一時停止を除いた作業時間（再開したタスクの場合のみ）
+    #[serde(
+        rename = "worked_seconds",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "as_optional_seconds"
+    )]
+    pub worked: Option<Duration>,
+}
+
+impl EntryDetail {
+    /// 進行中のエントリは `now` までの経過時間にする
+    pub fn new(entry: TimeEntry, now: DateTime<Local>) -> Self {
+        let elapsed = entry.end.unwrap_or(now).signed_duration_since(entry.start);
+        Self { entry, elapsed: elapsed.to_std().unwrap_or_default(), worked: None }
+    }
+
+    /// 一時停止を除いた作業時間を含める
+    pub fn with_worked(mut self, worked: Duration) -> Self {
+        self.worked = Some(worked);
+        self
+    }
+}
+
+/// 期間にかかっているエントリの一覧（`--json` での出力用）
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeriodEntries {
+    /// 期間の開始日
+    pub start: NaiveDate,
+
+    /// 期間の終了日（この日を含む）
+    pub end: NaiveDate,
+
+    /// 絞り込みに使ったタグ
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// エントリ（開始時刻の順）
+    pub entries: Vec<EntryDetail>,
+}
+
+impl PeriodEntries {
+    /// 期間と絞り込みのタグを添えてエントリをまとめる
+    pub fn new(range: DateRange, tags: &[String], entries: Vec<TimeEntry>, now: DateTime<Local>) -> Self {
+        let entries = entries.into_iter().map(|e| EntryDetail::new(e, now)).collect();
+        Self { start: range.start, end: range.end, tags: tags.to_vec(), entries }
+    }
+}
+
+/// 現在のタスクと今日のサマリー（`status --json` での出力用）
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CurrentStatus {
+    /// 進行中のエントリ
+    pub current: Option<EntryDetail>,
+
+    /// 一時停止中のエントリ（進行中のエントリがない場合のみ）
+    pub paused: Option<EntryDetail>,
+
+    /// 今日のサマリー
+    pub today: PeriodSummary,
+}
+
+/// 停止したエントリと今日のサマリー（`stop --json` での出力用）
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StopResult {
+    /// 停止したエントリ
+    pub stopped: EntryDetail,
+
+    /// 今日のサマリー
+    pub today: PeriodSummary,
+}
+
+/// タスクごとの集計結果を名前順の一覧にする
+pub fn task_totals(totals: &HashMap<String, Duration>) -> Vec<TaskTotal> {
+    let mut totals: Vec<_> = totals
+        .iter()
+        .map(|(task, duration)| TaskTotal { task: task.clone(), duration: *duration })
+        .collect();
+    totals.sort_by(|a, b| a.task.cmp(&b.task));
+    totals
+}
+
+/// 作業時間を秒数（端数は切り捨て）としてシリアライズする
+fn as_seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_secs())
+}
+
+/// 作業時間があれば秒数としてシリアライズする
+fn as_optional_seconds<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => as_seconds(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
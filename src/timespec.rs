@@ -0,0 +1,126 @@
+// コマンドラインで指定された時刻のパース
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use crate::error::TimeCheckerError;
+
+/// 時刻の文字列をパースする
+///
+/// 受け付ける形式:
+/// - `HH:MM` / `HH:MM:SS` : `base_date` の時刻
+/// - `YYYY-MM-DD HH:MM` / `YYYY-MM-DDTHH:MM`（秒も可）
+/// - RFC 3339（`2025-11-05T09:00:00+09:00`）
+pub fn parse_datetime(s: &str, base_date: NaiveDate) -> Result<DateTime<Local>, TimeCheckerError> {
+    let input = s.trim();
+    let invalid = || TimeCheckerError::InvalidTime(s.to_string());
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Local));
+    }
+
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(input, format) {
+            return to_local(naive).ok_or_else(invalid);
+        }
+    }
+
+    let time = parse_time(input).ok_or_else(invalid)?;
+    to_local(base_date.and_time(time)).ok_or_else(invalid)
+}
+
+/// `HH:MM` または `HH:MM:SS` 形式の時刻をパース
+pub fn parse_time(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(s.trim(), "%H:%M"))
+        .ok()
+}
+
+/// ローカル時刻に変換（夏時間で重複する時刻は早い方、存在しない時刻は None）
+fn to_local(naive: NaiveDateTime) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// 過去の時刻を指定する文字列をパースする（`start --at` などで使用）
+/// 時刻だけの指定が現在より未来になる場合は前日の時刻とみなす（深夜に前日の時刻を指定する場合）
+pub fn parse_past_datetime(s: &str, now: DateTime<Local>) -> Result<DateTime<Local>, TimeCheckerError> {
+    let parsed = parse_datetime(s, now.date_naive())?;
+
+    if parsed > now && parse_time(s).is_some() {
+        let yesterday = now.date_naive().pred_opt().ok_or_else(|| TimeCheckerError::InvalidTime(s.to_string()))?;
+        return parse_datetime(s, yesterday);
+    }
+
+    Ok(parsed)
+}
+
+/// 開始・終了時刻の組をパースする（`add --from --to` で使用）
+/// 終了時刻が時刻だけの指定で開始時刻以前になる場合は翌日の時刻とみなす（日付をまたぐ作業）
+pub fn parse_time_range(
+    from: &str,
+    to: &str,
+    base_date: NaiveDate,
+) -> Result<(DateTime<Local>, DateTime<Local>), TimeCheckerError> {
+    let start = parse_datetime(from, base_date)?;
+    let mut end = parse_datetime(to, start.date_naive())?;
+
+    if end <= start && parse_time(to).is_some() {
+        let next_day = start.date_naive().succ_opt().ok_or_else(|| TimeCheckerError::InvalidTime(to.to_string()))?;
+        end = parse_datetime(to, next_day)?;
+    }
+
+    Ok((start, end))
+}
+
+/// `10m`, `1h30m`, `90s` のような時間の長さをパースする
+/// 単位は h（時間）、m（分）、s（秒）。単位のない数値は分とみなす
+pub fn parse_duration(s: &str) -> Result<Duration, TimeCheckerError> {
+    let input = s.trim();
+    let invalid = || TimeCheckerError::InvalidTime(s.to_string());
+
+    if input.is_empty() {
+        return Err(invalid());
+    }
+
+    if let Ok(minutes) = input.parse::<u32>() {
+        return Ok(Duration::minutes(minutes.into()));
+    }
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let value: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+
+        // 大きすぎる値は範囲外としてエラーにする
+        let part = match c {
+            'h' => Duration::try_hours(value),
+            'm' => Duration::try_minutes(value),
+            's' => Duration::try_seconds(value),
+            _ => return Err(invalid()),
+        };
+        total = part.and_then(|p| total.checked_add(&p)).ok_or_else(invalid)?;
+    }
+
+    // 単位のない数字が末尾に残っている
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(total)
+}
+
+/// `--at` または `--ago` の指定から時刻を決める（どちらもなければ現在時刻）
+pub fn resolve_at(at: Option<&str>, ago: Option<&str>, now: DateTime<Local>) -> Result<DateTime<Local>, TimeCheckerError> {
+    match (at, ago) {
+        (Some(at), _) => parse_past_datetime(at, now),
+        (None, Some(ago)) => now
+            .checked_sub_signed(parse_duration(ago)?)
+            .ok_or_else(|| TimeCheckerError::InvalidTime(ago.to_string())),
+        (None, None) => Ok(now),
+    }
+}
@@ -1,89 +1,698 @@
 // ビジネスロジック（Tracker）
 
-use chrono::Local;
-use std::collections::HashMap;
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
 use std::time::Duration;
-use crate::data::{DataStore, TimeEntry};
+use crate::clock::{Clock, SystemClock};
+use crate::data::{self, DataStore, Entries, TimeEntry};
 use crate::error::TimeCheckerError;
+use crate::filter::TaskFilter;
+use crate::history::{Operation, OperationHistory, OperationKind};
+use crate::period::DateRange;
+use crate::storage::Storage;
+use crate::summary::{Summary, TagSummary, TaskTree};
+
+/// エントリの変更内容（None の項目は変更しない）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntryEdit {
+    /// 新しいタスク名
+    pub task: Option<String>,
+
+    /// 新しいメモ（`Some(None)` でメモを削除）
+    pub note: Option<Option<String>>,
+
+    /// 新しい開始時刻
+    pub start: Option<DateTime<Local>>,
+
+    /// 新しい終了時刻
+    pub end: Option<DateTime<Local>>,
+
+    /// 新しいタグ（空にするとタグを削除）
+    pub tags: Option<Vec<String>>,
+}
+
+/// インポートしなかった理由
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// 指定したIDのエントリと時間が重なっている
+    Overlapping(u64),
+    /// まだ終わっていない（未来のエントリは進行中のタスクと重なってしまうため）
+    NotFinished,
+    /// カレンダーに定義のないタイムゾーン（`TZID`）の時刻
+    UnknownTimeZone(String),
+    /// 展開できない繰り返しの規則（`RRULE`）
+    UnsupportedRecurrence(String),
+}
+
+/// インポートしなかったエントリ
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SkippedEntry {
+    /// エントリ（IDは未採番）
+    pub entry: TimeEntry,
+
+    /// インポートしなかった理由
+    pub reason: SkipReason,
+}
+
+/// インポートの結果
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ImportResult {
+    /// 追加したエントリ
+    pub added: Vec<TimeEntry>,
+
+    /// 追加しなかったエントリ
+    pub skipped: Vec<SkippedEntry>,
+}
 
 /// ビジネスロジックを担当する構造体
-pub struct Tracker {
-    store: DataStore,
+/// 保存先は `Storage` を実装した型から選べる（デフォルトはJSONファイルの `DataStore`）
+pub struct Tracker<S = DataStore> {
+    store: S,
+    clock: Arc<dyn Clock>,
+    history: Option<OperationHistory>,
 }
 
-impl Tracker {
-    /// 新しいTrackerインスタンスを作成
-    pub fn new(store: DataStore) -> Self {
-        Self { store }
+impl<S: Storage> Tracker<S> {
+    /// 新しいTrackerインスタンスを作成（システムの時計を使用）
+    pub fn new(store: S) -> Self {
+        Self::with_clock(store, Arc::new(SystemClock))
+    }
+
+    /// 時計を指定してTrackerインスタンスを作成
+    /// DataStoreと同じ時計を渡すと、両者の「現在時刻」が一致する
+    pub fn with_clock(store: S, clock: Arc<dyn Clock>) -> Self {
+        Self { store, clock, history: None }
+    }
+
+    /// 変更操作を記録する履歴を設定（`undo` / `redo` に必要）
+    pub fn with_history(mut self, history: OperationHistory) -> Self {
+        self.history = Some(history);
+        self
     }
 
-    /// DataStoreへの参照を取得
-    pub fn store(&self) -> &DataStore {
+    /// 保存先への参照を取得
+    pub fn store(&self) -> &S {
         &self.store
     }
 
+    /// 時計への参照を取得
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// 操作の履歴への参照を取得
+    pub fn history(&self) -> Option<&OperationHistory> {
+        self.history.as_ref()
+    }
+
     /// 新しいタスクを開始
     /// 進行中のタスクがあれば自動的に終了する
     pub fn start_task(&self, task: String, note: Option<String>) -> Result<(), TimeCheckerError> {
-        let mut entries = self.store.load()?;
-        let now = Local::now();
+        self.start_task_at(task, note, self.clock.now())
+    }
+
+    /// 指定した時刻に開始したものとして新しいタスクを開始
+    /// 進行中のタスクがあればその時刻で終了する
+    pub fn start_task_at(&self, task: String, note: Option<String>, at: DateTime<Local>) -> Result<(), TimeCheckerError> {
+        self.start_tagged_task_at(task, note, Vec::new(), at)?;
+        Ok(())
+    }
+
+    /// タグ付きで新しいタスクを開始し、開始したエントリを返す
+    pub fn start_tagged_task_at(
+        &self,
+        task: String,
+        note: Option<String>,
+        tags: Vec<String>,
+        at: DateTime<Local>,
+    ) -> Result<TimeEntry, TimeCheckerError> {
+        self.ensure_not_future(at)?;
+        self.mutate(OperationKind::Start, |entries| start_entry(entries, task, note, tags, at))
+    }
+
+    /// 以前に取り組んだタスクを、タスク名・メモ・タグを引き継いだ新しいエントリとして開始
+    /// `nth` は `recent_tasks` の順序で1から数える（1なら直前に終了したタスク）
+    pub fn continue_task(&self, nth: usize) -> Result<TimeEntry, TimeCheckerError> {
+        let at = self.clock.now();
+
+        self.mutate(OperationKind::Start, |entries| {
+            // 0番目のタスクはない
+            let previous = nth
+                .checked_sub(1)
+                .and_then(|i| recent_tasks(entries).into_iter().nth(i))
+                .cloned()
+                .ok_or(TimeCheckerError::NoRecentTask(nth))?;
+            start_entry(entries, previous.task, previous.note, previous.tags, at)
+        })
+    }
+
+    /// 最近終了したタスクをタスク名の重複を除いて新しい順に最大 `limit` 件取得（`continue` の候補）
+    /// 進行中のタスクと同じ名前のタスクは含めない
+    pub fn recent_tasks(&self, limit: usize) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        let entries = self.store.load()?;
+        Ok(recent_tasks(&entries).into_iter().take(limit).cloned().collect())
+    }
+
+    /// 現在のタスクを停止し、停止したエントリを返す
+    pub fn stop_task(&self) -> Result<TimeEntry, TimeCheckerError> {
+        self.stop_task_at(self.clock.now())
+    }
 
-        // 進行中のタスクがあれば終了する
-        if let Some(current) = entries.iter_mut().rev().find(|e| e.end.is_none()) {
-            current.end = Some(now);
+    /// 指定した時刻で現在のタスクを停止し、停止したエントリを返す
+    /// 一時停止中の場合は一時停止した時刻で終了したものとする
+    pub fn stop_task_at(&self, at: DateTime<Local>) -> Result<TimeEntry, TimeCheckerError> {
+        self.ensure_not_future(at)?;
+
+        self.mutate(OperationKind::Stop, |entries| {
+            // 進行中のタスクを見つけて終了
+            let Some(entry) = entries.iter_mut().rev().find(|e| e.end.is_none()) else {
+                let Some(paused) = find_paused(entries).cloned() else {
+                    return Err(TimeCheckerError::NoActiveTask);
+                };
+                clear_paused(entries);
+                return Ok(TimeEntry { paused: false, ..paused });
+            };
+
+            entry.end = Some(at);
+            let entry = entry.clone();
+            validate_entry(entries, &entry)?;
+            Ok(entry)
+        })
+    }
+
+    /// 現在のタスクを一時停止し、一時停止したエントリを返す
+    pub fn pause_task(&self) -> Result<TimeEntry, TimeCheckerError> {
+        self.pause_task_at(self.clock.now())
+    }
+
+    /// 指定した時刻で現在のタスクを一時停止
+    /// エントリはその時刻で終了し、`resume` で同じタスク名・メモの新しいエントリとして再開できる
+    pub fn pause_task_at(&self, at: DateTime<Local>) -> Result<TimeEntry, TimeCheckerError> {
+        self.ensure_not_future(at)?;
+
+        self.mutate(OperationKind::Pause, |entries| {
+            let entry = entries
+                .iter_mut()
+                .rev()
+                .find(|e| e.end.is_none())
+                .ok_or(TimeCheckerError::NoActiveTask)?;
+
+            entry.end = Some(at);
+            entry.paused = true;
+            let entry = entry.clone();
+            validate_entry(entries, &entry)?;
+            Ok(entry)
+        })
+    }
+
+    /// 一時停止中のタスクを再開し、再開したエントリを返す
+    pub fn resume_task(&self) -> Result<TimeEntry, TimeCheckerError> {
+        self.resume_task_at(self.clock.now())
+    }
+
+    /// 指定した時刻に一時停止中のタスクを再開
+    pub fn resume_task_at(&self, at: DateTime<Local>) -> Result<TimeEntry, TimeCheckerError> {
+        self.ensure_not_future(at)?;
+
+        self.mutate(OperationKind::Resume, |entries| {
+            let paused = find_paused(entries).cloned().ok_or(TimeCheckerError::NotPaused)?;
+            clear_paused(entries);
+
+            let new_entry = TimeEntry {
+                id: entries.next_id(),
+                task: paused.task,
+                start: at,
+                end: None,
+                note: paused.note,
+                paused: false,
+                resumed_from: Some(paused.id),
+                tags: paused.tags,
+            };
+
+            validate_entry(entries, &new_entry)?;
+            entries.push(new_entry.clone());
+            Ok(new_entry)
+        })
+    }
+
+    /// 一時停止中のタスクを取得（進行中のタスクがある場合は None）
+    pub fn get_paused_task(&self) -> Result<Option<TimeEntry>, TimeCheckerError> {
+        let entries = self.store.load()?;
+        Ok(find_paused(&entries).cloned())
+    }
+
+    /// 一時停止をはさんで続けている作業の、一時停止を除いた作業時間
+    /// `resumed_from` をたどって再開前のエントリの時間も合計する（進行中のエントリは現在時刻まで）
+    pub fn get_worked_time(&self, entry: &TimeEntry) -> Result<chrono::Duration, TimeCheckerError> {
+        let entries = self.store.load()?;
+        let by_id: HashMap<u64, &TimeEntry> = entries.iter().map(|e| (e.id, e)).collect();
+        let now = self.clock.now();
+
+        let mut total = chrono::Duration::zero();
+        let mut current = Some(entry);
+        let mut visited = 0;
+        while let Some(entry) = current {
+            total += entry.end.unwrap_or(now).signed_duration_since(entry.start);
+
+            // 手で編集されたファイルで循環していても止まるようにする
+            visited += 1;
+            if visited > entries.len() {
+                break;
+            }
+            current = entry.resumed_from.and_then(|id| by_id.get(&id).copied());
         }
 
-        // 新しいタスクを追加
-        let new_entry = TimeEntry {
-            task,
-            start: now,
-            end: None,
-            note,
-        };
+        Ok(total)
+    }
 
-        entries.push(new_entry);
-        self.store.save(&entries)?;
+    /// 開始・終了時刻を指定して終了済みのエントリを追加（記録し忘れた会議など）
+    pub fn add_entry(
+        &self,
+        task: String,
+        note: Option<String>,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    ) -> Result<TimeEntry, TimeCheckerError> {
+        self.add_tagged_entry(task, note, Vec::new(), start, end)
+    }
 
+    /// タグ付きで終了済みのエントリを追加
+    /// 終了時刻が未来のエントリは進行中のタスクと重なってしまうので追加できない
+    pub fn add_tagged_entry(
+        &self,
+        task: String,
+        note: Option<String>,
+        tags: Vec<String>,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    ) -> Result<TimeEntry, TimeCheckerError> {
+        self.ensure_not_future(end)?;
+        self.mutate(OperationKind::Add, |entries| {
+            let new_entry = TimeEntry {
+                id: entries.next_id(),
+                task,
+                start,
+                end: Some(end),
+                note,
+                tags: data::dedup_tags(tags),
+                ..Default::default()
+            };
+
+            validate_entry(entries, &new_entry)?;
+            entries.push(new_entry.clone());
+            Ok(new_entry)
+        })
+    }
+
+    /// 終了済みのエントリをまとめて追加する
+    /// 既存のエントリや先に追加したエントリと重なるもの、まだ終わっていないものは追加せずに結果に含める
+    pub fn import_entries(&self, candidates: Vec<TimeEntry>) -> Result<ImportResult, TimeCheckerError> {
+        let now = self.clock.now();
+        self.mutate(OperationKind::Import, |entries| import_into(entries, candidates, now))
+    }
+
+    /// 保存せずに、インポートした場合の結果を返す（追加するエントリのIDは仮のもの）
+    pub fn preview_import(&self, candidates: Vec<TimeEntry>) -> Result<ImportResult, TimeCheckerError> {
+        let mut entries = self.store.load_entries()?;
+        import_into(&mut entries, candidates, self.clock.now())
+    }
+
+    /// 未来の時刻での開始・停止を防ぐ
+    fn ensure_not_future(&self, at: DateTime<Local>) -> Result<(), TimeCheckerError> {
+        if at > self.clock.now() {
+            return Err(TimeCheckerError::InvalidTimeRange(format!(
+                "未来の時刻 {} は指定できません",
+                at.format("%Y-%m-%d %H:%M")
+            )));
+        }
         Ok(())
     }
 
-    /// 現在のタスクを停止
-    pub fn stop_task(&self) -> Result<(), TimeCheckerError> {
-        let mut entries = self.store.load()?;
-        let now = Local::now();
+    /// 記録済みのエントリを編集
+    /// 終了時刻が開始時刻より前になる変更や、他のエントリと時間が重なる変更はエラーになる
+    /// 未来の時刻への変更も、これから開始するタスクと重なってしまうのでエラーになる
+    pub fn edit_entry(&self, id: u64, edit: EntryEdit) -> Result<TimeEntry, TimeCheckerError> {
+        self.mutate(OperationKind::Edit, |entries| {
+            let index = entries
+                .iter()
+                .position(|e| e.id == id)
+                .ok_or(TimeCheckerError::EntryNotFound(id))?;
 
-        // 進行中のタスクを見つけて終了
-        let found = entries.iter_mut().rev().find(|e| e.end.is_none());
+            let mut updated = entries[index].clone();
+            if let Some(task) = edit.task {
+                updated.task = task;
+            }
+            if let Some(note) = edit.note {
+                updated.note = note;
+            }
+            if let Some(start) = edit.start {
+                updated.start = start;
+            }
+            if let Some(end) = edit.end {
+                updated.end = Some(end);
+            }
+            if let Some(tags) = edit.tags {
+                updated.tags = data::dedup_tags(tags);
+            }
 
-        match found {
-            Some(entry) => {
-                entry.end = Some(now);
-                self.store.save(&entries)?;
-                Ok(())
+            for at in edit.start.iter().chain(edit.end.iter()) {
+                self.ensure_not_future(*at)?;
             }
-            None => Err(TimeCheckerError::NoActiveTask),
-        }
+            validate_entry(entries, &updated)?;
+
+            entries[index] = updated.clone();
+            Ok(updated)
+        })
+    }
+
+    /// 記録済みのエントリを削除し、削除したエントリを返す
+    pub fn delete_entry(&self, id: u64) -> Result<TimeEntry, TimeCheckerError> {
+        self.mutate(OperationKind::Delete, |entries| {
+            let index = entries
+                .iter()
+                .position(|e| e.id == id)
+                .ok_or(TimeCheckerError::EntryNotFound(id))?;
+
+            Ok(entries.remove(index))
+        })
+    }
+
+    /// 直前の変更操作を最大 `count` 回取り消し、取り消した操作を新しい順に返す
+    /// 取り消した操作は `redo` でやり直せる
+    pub fn undo(&self, count: usize) -> Result<Vec<Operation>, TimeCheckerError> {
+        self.step_history(count, true)
+    }
+
+    /// 取り消した操作を最大 `count` 回やり直し、やり直した操作を古い順に返す
+    pub fn redo(&self, count: usize) -> Result<Vec<Operation>, TimeCheckerError> {
+        self.step_history(count, false)
+    }
+
+    /// 履歴から操作を取り出してエントリに適用し、反対側の履歴へ移す
+    /// 途中で適用できない操作があればそこで止める（1件も適用できなければエラー）
+    fn step_history(&self, count: usize, undo: bool) -> Result<Vec<Operation>, TimeCheckerError> {
+        let empty = || if undo { TimeCheckerError::NothingToUndo } else { TimeCheckerError::NothingToRedo };
+        let history = self.history.as_ref().ok_or_else(empty)?;
+
+        // 履歴のロックを取ってからデータを更新し、両者の食い違いを防ぐ
+        history.modify(|stacks| {
+            let (from, to) = if undo {
+                (&mut stacks.undo, &mut stacks.redo)
+            } else {
+                (&mut stacks.redo, &mut stacks.undo)
+            };
+            let mut applied = Vec::new();
+
+            while applied.len() < count {
+                let Some(operation) = from.pop() else { break };
+                let result = self.store.transaction(|entries| {
+                    if undo { operation.revert(entries) } else { operation.reapply(entries) }
+                });
+
+                if let Err(e) = result {
+                    from.push(operation);
+                    if applied.is_empty() {
+                        return Err(e);
+                    }
+                    break;
+                }
+                to.push(operation.clone());
+                applied.push(operation);
+            }
+
+            if applied.is_empty() {
+                return Err(empty());
+            }
+            Ok(applied)
+        })
+    }
+
+    /// 読み込み → 変更 → 保存 を行い、履歴があれば変わったエントリを記録する
+    fn mutate<T>(
+        &self,
+        kind: OperationKind,
+        update: impl FnOnce(&mut Entries) -> Result<T, TimeCheckerError>,
+    ) -> Result<T, TimeCheckerError> {
+        let Some(history) = &self.history else {
+            return self.store.transaction(update);
+        };
+
+        let (result, operation) = self.store.transaction(|entries| {
+            let before = entries.clone();
+            let result = update(entries)?;
+            entries.assign_ids();
+            Ok((result, Operation::new(kind, self.clock.now(), &before, entries)))
+        })?;
+
+        // undo は 履歴 → データ の順にロックするので、データのロックを離してから履歴を更新する
+        history.record(operation)?;
+        Ok(result)
     }
 
     /// 今日のタスクのサマリーを取得（タスク名ごとに集計）
     pub fn get_today_summary(&self) -> Result<HashMap<String, Duration>, TimeCheckerError> {
-        let entries = self.store.get_today_entries()?;
+        let today = self.clock.today();
+        self.get_summary(DateRange::single(today))
+    }
+
+    /// 指定期間のタスクのサマリーを取得（タスク名ごとに集計）
+    /// 日付をまたぐエントリは期間内に収まる部分だけを集計する
+    pub fn get_summary(&self, range: DateRange) -> Result<HashMap<String, Duration>, TimeCheckerError> {
+        self.get_tagged_summary(range, &[])
+    }
+
+    /// 指定したタグのいずれかを持つエントリだけを対象に、指定期間のサマリーを取得（タスク名ごとに集計）
+    /// タグを指定しなければすべてのエントリが対象になる
+    pub fn get_tagged_summary(
+        &self,
+        range: DateRange,
+        tags: &[String],
+    ) -> Result<HashMap<String, Duration>, TimeCheckerError> {
+        let entries = self.store.get_entries_in_range(range)?;
+        let (from, to) = range.bounds();
+        let now = self.clock.now();
         let mut summary: HashMap<String, Duration> = HashMap::new();
 
-        for entry in entries {
-            // 終了時刻がない場合は現在時刻を使用
-            let end = entry.end.unwrap_or_else(Local::now);
-            let duration = end.signed_duration_since(entry.start);
-
-            if duration.num_milliseconds() >= 0 {
-                let std_duration = Duration::from_millis(duration.num_milliseconds() as u64);
-                summary
-                    .entry(entry.task.clone())
-                    .and_modify(|d| *d += std_duration)
-                    .or_insert(std_duration);
+        for entry in entries.iter().filter(|e| e.has_any_tag(tags)) {
+            let duration = entry.duration_within(from, to, now);
+            add_duration(&mut summary, &entry.task, duration);
+        }
+
+        Ok(summary)
+    }
+
+    /// 指定期間のサマリーを取得し、除外フィルタに一致するタスクを別枠に振り分ける
+    pub fn get_filtered_summary(&self, range: DateRange, exclude: &TaskFilter) -> Result<Summary, TimeCheckerError> {
+        let totals = self.get_summary(range)?;
+        Ok(Summary::new(totals, exclude))
+    }
+
+    /// 指定期間のサマリーを `/` で区切ったタスク名の階層ごとに取得（除外フィルタに一致するタスクは含めない）
+    pub fn get_task_tree(&self, range: DateRange, exclude: &TaskFilter) -> Result<TaskTree, TimeCheckerError> {
+        let summary = self.get_filtered_summary(range, exclude)?;
+        Ok(TaskTree::new(&summary.included))
+    }
+
+    /// 指定期間のサマリーをタグごとに取得
+    /// 除外フィルタに一致するタスクはタグごとには集計せず別枠に振り分ける
+    /// （`tags` を指定した場合はそのいずれかを持つエントリだけを集計する）
+    pub fn get_tag_summary(
+        &self,
+        range: DateRange,
+        tags: &[String],
+        exclude: &TaskFilter,
+    ) -> Result<TagSummary, TimeCheckerError> {
+        let entries = self.store.get_entries_in_range(range)?;
+        let (from, to) = range.bounds();
+        let now = self.clock.now();
+        let mut summary = TagSummary::default();
+
+        for entry in entries.iter().filter(|e| e.has_any_tag(tags)) {
+            let duration = entry.duration_within(from, to, now);
+            if exclude.matches(&entry.task) {
+                add_duration(&mut summary.excluded, &entry.task, duration);
+                continue;
+            }
+            if entry.tags.is_empty() {
+                summary.untagged += duration.to_std().unwrap_or_default();
+            }
+            for tag in &entry.tags {
+                add_duration(&mut summary.tags, tag, duration);
             }
         }
 
         Ok(summary)
     }
+
+    /// 指定期間にかかっているエントリを開始時刻の順に取得
+    pub fn list_entries(&self, range: DateRange) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        self.list_tagged_entries(range, &[])
+    }
+
+    /// 指定したタグのいずれかを持つ、指定期間にかかっているエントリを開始時刻の順に取得
+    pub fn list_tagged_entries(&self, range: DateRange, tags: &[String]) -> Result<Vec<TimeEntry>, TimeCheckerError> {
+        let mut entries = self.store.get_entries_in_range(range)?;
+        entries.retain(|e| e.has_any_tag(tags));
+        entries.sort_by_key(|e| e.start);
+        Ok(entries)
+    }
+
+    /// 指定期間のタスクのサマリーを日ごとに取得
+    /// 日付をまたぐエントリは0時で分割して各日に振り分ける
+    pub fn get_daily_summary(
+        &self,
+        range: DateRange,
+    ) -> Result<BTreeMap<NaiveDate, HashMap<String, Duration>>, TimeCheckerError> {
+        self.get_tagged_daily_summary(range, &[])
+    }
+
+    /// 指定したタグのいずれかを持つエントリだけを対象に、指定期間のサマリーを日ごとに取得
+    pub fn get_tagged_daily_summary(
+        &self,
+        range: DateRange,
+        tags: &[String],
+    ) -> Result<BTreeMap<NaiveDate, HashMap<String, Duration>>, TimeCheckerError> {
+        let entries = self.store.get_entries_in_range(range)?;
+        let now = self.clock.now();
+        let mut daily: BTreeMap<NaiveDate, HashMap<String, Duration>> = BTreeMap::new();
+
+        for entry in entries.into_iter().filter(|e| e.has_any_tag(tags)) {
+            for (day, duration) in entry.split_by_day(now) {
+                if range.contains(day) {
+                    add_duration(daily.entry(day).or_default(), &entry.task, duration);
+                }
+            }
+        }
+
+        Ok(daily)
+    }
+}
+
+/// エントリの時刻が正しいか、他のエントリと重なっていないかを検証
+fn validate_entry(entries: &[TimeEntry], candidate: &TimeEntry) -> Result<(), TimeCheckerError> {
+    if let Some(end) = candidate.end
+        && end < candidate.start
+    {
+        return Err(TimeCheckerError::InvalidTimeRange(format!(
+            "終了時刻 {} が開始時刻 {} より前です",
+            end.format("%Y-%m-%d %H:%M"),
+            candidate.start.format("%Y-%m-%d %H:%M")
+        )));
+    }
+
+    match entries
+        .iter()
+        .find(|e| e.id != candidate.id && e.conflicts_with(candidate))
+    {
+        Some(other) => Err(TimeCheckerError::OverlappingEntry(other.id)),
+        None => Ok(()),
+    }
+}
+
+/// 進行中のタスクを終了して新しいエントリを追加し、追加したエントリを返す
+fn start_entry(
+    entries: &mut Entries,
+    task: String,
+    note: Option<String>,
+    tags: Vec<String>,
+    at: DateTime<Local>,
+) -> Result<TimeEntry, TimeCheckerError> {
+    // 一時停止中のタスクは再開しないものとして扱う
+    clear_paused(entries);
+
+    // 進行中のタスクがあれば終了する
+    if let Some(current) = entries.iter_mut().rev().find(|e| e.end.is_none()) {
+        current.end = Some(at);
+        let current = current.clone();
+        validate_entry(entries, &current)?;
+    }
+
+    // 新しいタスクを追加
+    let new_entry = TimeEntry {
+        id: entries.next_id(),
+        task,
+        start: at,
+        end: None,
+        note,
+        tags: data::dedup_tags(tags),
+        ..Default::default()
+    };
+
+    validate_entry(entries, &new_entry)?;
+    entries.push(new_entry.clone());
+    Ok(new_entry)
+}
+
+/// 終了済みのエントリを終了時刻の新しい順に、タスク名の重複を除いて列挙する
+fn recent_tasks(entries: &[TimeEntry]) -> Vec<&TimeEntry> {
+    let running: Vec<&str> = entries.iter().filter(|e| e.end.is_none()).map(|e| e.task.as_str()).collect();
+
+    let mut finished: Vec<&TimeEntry> = entries.iter().filter(|e| e.end.is_some()).collect();
+    finished.sort_by_key(|e| std::cmp::Reverse(e.end));
+
+    let mut seen = HashSet::new();
+    finished
+        .into_iter()
+        .filter(|e| !running.contains(&e.task.as_str()) && seen.insert(e.task.as_str()))
+        .collect()
+}
+
+/// 開始時刻の順にエントリを追加する（重なるものや終わっていないものは追加しない）
+fn import_into(
+    entries: &mut Entries,
+    mut candidates: Vec<TimeEntry>,
+    now: DateTime<Local>,
+) -> Result<ImportResult, TimeCheckerError> {
+    candidates.sort_by_key(|e| e.start);
+    let mut result = ImportResult::default();
+
+    for candidate in candidates {
+        if candidate.end.is_none_or(|end| end > now) {
+            result.skipped.push(SkippedEntry { entry: candidate, reason: SkipReason::NotFinished });
+            continue;
+        }
+
+        let entry = TimeEntry { id: entries.next_id(), ..candidate };
+        match validate_entry(entries, &entry) {
+            Ok(()) => {
+                entries.push(entry.clone());
+                result.added.push(entry);
+            }
+            Err(TimeCheckerError::OverlappingEntry(id)) => {
+                let entry = TimeEntry { id: 0, ..entry };
+                result.skipped.push(SkippedEntry { entry, reason: SkipReason::Overlapping(id) });
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(result)
+}
+
+/// 一時停止中のエントリを探す（進行中のエントリがある場合は None）
+fn find_paused(entries: &[TimeEntry]) -> Option<&TimeEntry> {
+    if entries.iter().any(|e| e.end.is_none()) {
+        return None;
+    }
+    entries.iter().rev().find(|e| e.paused)
+}
+
+/// 一時停止中の印をすべて外す
+fn clear_paused(entries: &mut [TimeEntry]) {
+    for entry in entries.iter_mut().filter(|e| e.paused) {
+        entry.paused = false;
+    }
+}
+
+/// サマリーにタスクの作業時間を加算（負の時間は無視）
+fn add_duration(summary: &mut HashMap<String, Duration>, task: &str, duration: chrono::Duration) {
+    if let Ok(std_duration) = duration.to_std() {
+        summary
+            .entry(task.to_string())
+            .and_modify(|d| *d += std_duration)
+            .or_insert(std_duration);
+    }
 }
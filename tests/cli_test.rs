@@ -1,5 +1,8 @@
 use clap::Parser;
-use time_checker::cli::{Cli, Commands};
+use time_checker::cli::{Cli, Commands, ImportFormat};
+use time_checker::config::StorageBackend;
+use time_checker::export::{ExportFormat, ExportKind, Granularity};
+use time_checker::report::{MarkdownStyle, ReportFormat};
 
 #[test]
 fn test_cli_start_command() {
@@ -7,7 +10,7 @@ fn test_cli_start_command() {
     let cli = Cli::parse_from(args);
 
     match cli.command {
-        Commands::Start { task, note } => {
+        Commands::Start { task, note, .. } => {
             assert_eq!(task, "プログラミング");
             assert_eq!(note, None);
         }
@@ -21,7 +24,7 @@ fn test_cli_start_command_with_note() {
     let cli = Cli::parse_from(args);
 
     match cli.command {
-        Commands::Start { task, note } => {
+        Commands::Start { task, note, .. } => {
             assert_eq!(task, "ドキュメント作成");
             assert_eq!(note, Some("設計書更新".to_string()));
         }
@@ -35,7 +38,7 @@ fn test_cli_start_command_with_note_short() {
     let cli = Cli::parse_from(args);
 
     match cli.command {
-        Commands::Start { task, note } => {
+        Commands::Start { task, note, .. } => {
             assert_eq!(task, "会議");
             assert_eq!(note, Some("週次定例".to_string()));
         }
@@ -49,7 +52,7 @@ fn test_cli_stop_command() {
     let cli = Cli::parse_from(args);
 
     match cli.command {
-        Commands::Stop => {}
+        Commands::Stop { .. } => {}
         _ => panic!("Expected Stop command"),
     }
 }
@@ -71,7 +74,7 @@ fn test_cli_show_command_default() {
     let cli = Cli::parse_from(args);
 
     match cli.command {
-        Commands::Show { period } => {
+        Commands::Show { period, .. } => {
             assert_eq!(period, "today");
         }
         _ => panic!("Expected Show command"),
@@ -84,7 +87,7 @@ fn test_cli_show_command_with_period() {
     let cli = Cli::parse_from(args);
 
     match cli.command {
-        Commands::Show { period } => {
+        Commands::Show { period, .. } => {
             assert_eq!(period, "week");
         }
         _ => panic!("Expected Show command"),
@@ -97,10 +100,330 @@ fn test_cli_task_with_spaces() {
     let cli = Cli::parse_from(args);
 
     match cli.command {
-        Commands::Start { task, note } => {
+        Commands::Start { task, note, .. } => {
             assert_eq!(task, "バグ修正 issue #123");
             assert_eq!(note, None);
         }
         _ => panic!("Expected Start command"),
     }
 }
+
+#[test]
+fn test_cli_show_command_with_exclude() {
+    // カンマ区切りは TaskFilter::parse_args で分割する（正規表現の中のカンマを区切らないため）
+    let args = vec!["time-checker", "show", "today", "--exclude", "昼休憩,打ち合わせ", "--exclude", "/x{1,3}/"];
+    let cli = Cli::parse_from(args);
+
+    match cli.command {
+        Commands::Show { exclude, no_exclude, .. } => {
+            assert_eq!(exclude, vec!["昼休憩,打ち合わせ", "/x{1,3}/"]);
+            assert!(!no_exclude);
+        }
+        _ => panic!("Expected Show command"),
+    }
+
+    // --exclude と --no-exclude は同時に指定できない
+    let args = vec!["time-checker", "show", "--exclude", "昼休憩", "--no-exclude"];
+    assert!(Cli::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_cli_list_command() {
+    let cli = Cli::parse_from(vec!["time-checker", "list"]);
+    match cli.command {
+        Commands::List { period, .. } => assert_eq!(period, "today"),
+        _ => panic!("Expected List command"),
+    }
+
+    let cli = Cli::parse_from(vec!["time-checker", "list", "last-week"]);
+    match cli.command {
+        Commands::List { period, .. } => assert_eq!(period, "last-week"),
+        _ => panic!("Expected List command"),
+    }
+}
+
+#[test]
+fn test_cli_edit_and_delete_commands() {
+    let args = vec!["time-checker", "edit", "3", "--task", "会議", "--start", "09:15", "--end", "10:00"];
+    let cli = Cli::parse_from(args);
+
+    match cli.command {
+        Commands::Edit { id, task, note, start, end, .. } => {
+            assert_eq!(id, 3);
+            assert_eq!(task, Some("会議".to_string()));
+            assert_eq!(note, None);
+            assert_eq!(start, Some("09:15".to_string()));
+            assert_eq!(end, Some("10:00".to_string()));
+        }
+        _ => panic!("Expected Edit command"),
+    }
+
+    let cli = Cli::parse_from(vec!["time-checker", "delete", "7"]);
+    match cli.command {
+        Commands::Delete { id } => assert_eq!(id, 7),
+        _ => panic!("Expected Delete command"),
+    }
+
+    // IDは数値のみ
+    assert!(Cli::try_parse_from(vec!["time-checker", "delete", "abc"]).is_err());
+}
+
+#[test]
+fn test_cli_start_and_stop_with_time() {
+    let cli = Cli::parse_from(vec!["time-checker", "start", "会議", "--at", "10:00"]);
+    match cli.command {
+        Commands::Start { task, at, ago, .. } => {
+            assert_eq!(task, "会議");
+            assert_eq!(at, Some("10:00".to_string()));
+            assert_eq!(ago, None);
+        }
+        _ => panic!("Expected Start command"),
+    }
+
+    let cli = Cli::parse_from(vec!["time-checker", "stop", "--ago", "15m"]);
+    match cli.command {
+        Commands::Stop { at, ago } => {
+            assert_eq!(at, None);
+            assert_eq!(ago, Some("15m".to_string()));
+        }
+        _ => panic!("Expected Stop command"),
+    }
+
+    // --at と --ago は同時に指定できない
+    let args = vec!["time-checker", "stop", "--at", "10:00", "--ago", "15m"];
+    assert!(Cli::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_cli_add_command() {
+    let args = vec![
+        "time-checker", "add", "会議", "--from", "14:00", "--to", "15:00", "--date", "2025-11-04", "-n", "週次定例",
+    ];
+    let cli = Cli::parse_from(args);
+
+    match cli.command {
+        Commands::Add { task, from, to, date, note, .. } => {
+            assert_eq!(task, "会議");
+            assert_eq!(from, "14:00");
+            assert_eq!(to, "15:00");
+            assert_eq!(date, chrono::NaiveDate::from_ymd_opt(2025, 11, 4));
+            assert_eq!(note, Some("週次定例".to_string()));
+        }
+        _ => panic!("Expected Add command"),
+    }
+
+    // --from と --to は必須
+    assert!(Cli::try_parse_from(vec!["time-checker", "add", "会議", "--from", "14:00"]).is_err());
+}
+
+#[test]
+fn test_cli_migrate_command() {
+    let cli = Cli::parse_from(vec!["time-checker", "migrate", "sqlite", "--file", "old.json"]);
+    match cli.command {
+        Commands::Migrate { to, from, file, force } => {
+            assert_eq!(to, StorageBackend::Sqlite);
+            assert_eq!(from, None);
+            assert_eq!(file, Some(std::path::PathBuf::from("old.json")));
+            assert!(!force);
+        }
+        _ => panic!("Expected Migrate command"),
+    }
+
+    let cli = Cli::parse_from(vec!["time-checker", "migrate", "json", "--from", "journal", "--force"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Migrate { to: StorageBackend::Json, from: Some(StorageBackend::Journal), force: true, .. }
+    ));
+
+    assert!(Cli::try_parse_from(vec!["time-checker", "migrate", "csv"]).is_err());
+}
+
+#[test]
+fn test_cli_undo_and_redo_commands() {
+    let cli = Cli::parse_from(vec!["time-checker", "undo"]);
+    assert!(matches!(cli.command, Commands::Undo { count: 1 }));
+
+    let cli = Cli::parse_from(vec!["time-checker", "undo", "3"]);
+    assert!(matches!(cli.command, Commands::Undo { count: 3 }));
+
+    let cli = Cli::parse_from(vec!["time-checker", "redo", "2"]);
+    assert!(matches!(cli.command, Commands::Redo { count: 2 }));
+
+    assert!(Cli::try_parse_from(vec!["time-checker", "undo", "0"]).is_err());
+    assert!(Cli::try_parse_from(vec!["time-checker", "redo", "0"]).is_err());
+}
+
+#[test]
+fn test_cli_pause_and_resume_commands() {
+    let cli = Cli::parse_from(vec!["time-checker", "pause"]);
+    assert!(matches!(cli.command, Commands::Pause));
+
+    let cli = Cli::parse_from(vec!["time-checker", "resume"]);
+    assert!(matches!(cli.command, Commands::Resume));
+}
+
+#[test]
+fn test_cli_continue_command() {
+    let cli = Cli::parse_from(vec!["time-checker", "continue"]);
+    assert!(matches!(cli.command, Commands::Continue { nth: None }));
+
+    let cli = Cli::parse_from(vec!["time-checker", "continue", "2"]);
+    assert!(matches!(cli.command, Commands::Continue { nth: Some(2) }));
+
+    // restart は continue の別名
+    let cli = Cli::parse_from(vec!["time-checker", "restart", "3"]);
+    assert!(matches!(cli.command, Commands::Continue { nth: Some(3) }));
+
+    // 1から数えるので 0 はエラー
+    assert!(Cli::try_parse_from(vec!["time-checker", "continue", "0"]).is_err());
+}
+
+#[test]
+fn test_cli_tags() {
+    let cli = Cli::parse_from(vec!["time-checker", "start", "実装", "+backend", "+urgent", "-n", "API"]);
+    match cli.command {
+        Commands::Start { task, tags, note, .. } => {
+            assert_eq!(task, "実装");
+            assert_eq!(tags, vec!["backend", "urgent"]);
+            assert_eq!(note, Some("API".to_string()));
+        }
+        _ => panic!("Expected Start command"),
+    }
+
+    // + のない位置引数はタスク名の続きとみなさずエラーにする
+    assert!(Cli::try_parse_from(vec!["time-checker", "start", "実装", "backend"]).is_err());
+    assert!(Cli::try_parse_from(vec!["time-checker", "start", "実装", "+"]).is_err());
+
+    let cli = Cli::parse_from(vec!["time-checker", "show", "week", "--tag", "backend,+urgent", "--by-tag"]);
+    match cli.command {
+        Commands::Show { tags, by_tag, .. } => {
+            assert_eq!(tags, vec!["backend", "urgent"]);
+            assert!(by_tag);
+        }
+        _ => panic!("Expected Show command"),
+    }
+
+    let cli = Cli::parse_from(vec!["time-checker", "list", "--tag", "frontend"]);
+    assert!(matches!(cli.command, Commands::List { tags, .. } if tags == vec!["frontend"]));
+
+    let cli = Cli::parse_from(vec!["time-checker", "edit", "3", "--tags", ""]);
+    assert!(matches!(cli.command, Commands::Edit { tags: Some(tags), .. } if tags.is_empty()));
+}
+
+#[test]
+fn test_cli_show_tree() {
+    let cli = Cli::parse_from(vec!["time-checker", "show", "week", "--tree"]);
+    assert!(matches!(cli.command, Commands::Show { tree: true, depth: None, .. }));
+
+    let cli = Cli::parse_from(vec!["time-checker", "show", "--depth", "2"]);
+    assert!(matches!(cli.command, Commands::Show { tree: false, depth: Some(2), .. }));
+
+    assert!(Cli::try_parse_from(vec!["time-checker", "show", "--depth", "0"]).is_err());
+    assert!(Cli::try_parse_from(vec!["time-checker", "show", "--tree", "--by-tag"]).is_err());
+}
+
+#[test]
+fn test_cli_export_command() {
+    let cli = Cli::parse_from(vec!["time-checker", "export", "entries"]);
+    match cli.command {
+        Commands::Export { kind, period, per, output, bom, .. } => {
+            assert_eq!(kind, ExportKind::Entries);
+            assert_eq!(period, "today");
+            assert_eq!(per, Granularity::Day);
+            assert_eq!(output, None);
+            assert!(!bom);
+        }
+        _ => panic!("Expected Export command"),
+    }
+
+    let args = vec!["time-checker", "export", "summary", "last-month", "--per", "week", "-o", "out.csv", "--bom"];
+    let cli = Cli::parse_from(args);
+    assert!(matches!(
+        cli.command,
+        Commands::Export { kind: ExportKind::Summary, per: Granularity::Week, bom: true, output: Some(_), .. }
+    ));
+
+    let cli = Cli::parse_from(vec!["time-checker", "export", "entries", "month", "--format", "ics"]);
+    assert!(matches!(cli.command, Commands::Export { kind: ExportKind::Entries, format: ExportFormat::Ics, .. }));
+
+    assert!(Cli::try_parse_from(vec!["time-checker", "export", "xlsx"]).is_err());
+    assert!(Cli::try_parse_from(vec!["time-checker", "export", "entries", "--format", "xlsx"]).is_err());
+}
+
+#[test]
+fn test_cli_import_command() {
+    let cli = Cli::parse_from(vec!["time-checker", "import", "calendar.ics"]);
+    match cli.command {
+        Commands::Import { file, period, format, patterns, tags, dry_run } => {
+            assert_eq!(file.to_str(), Some("calendar.ics"));
+            assert_eq!(period, "today");
+            assert_eq!(format, ImportFormat::Ics);
+            assert!(patterns.is_empty());
+            assert!(tags.is_empty());
+            assert!(!dry_run);
+        }
+        _ => panic!("Expected Import command"),
+    }
+
+    let args = vec!["time-checker", "import", "calendar.ics", "week", "--match", "定例,MTG-*", "--tag", "cal", "--dry-run"];
+    match Cli::parse_from(args).command {
+        Commands::Import { period, patterns, tags, dry_run, .. } => {
+            assert_eq!(period, "week");
+            assert_eq!(patterns, vec!["定例,MTG-*"]);
+            assert_eq!(tags, vec!["cal"]);
+            assert!(dry_run);
+        }
+        _ => panic!("Expected Import command"),
+    }
+
+    // 正規表現の中のカンマでは区切らない
+    let args = vec!["time-checker", "import", "calendar.ics", "--match", "/^MTG-\\d{1,3}$/", "--match", "定例"];
+    match Cli::parse_from(args).command {
+        Commands::Import { patterns, .. } => assert_eq!(patterns, vec!["/^MTG-\\d{1,3}$/", "定例"]),
+        _ => panic!("Expected Import command"),
+    }
+
+    assert!(Cli::try_parse_from(vec!["time-checker", "import"]).is_err());
+    assert!(Cli::try_parse_from(vec!["time-checker", "import", "a.csv", "--format", "csv"]).is_err());
+}
+
+#[test]
+fn test_cli_show_markdown_format() {
+    let cli = Cli::parse_from(vec!["time-checker", "show"]);
+    assert!(matches!(
+        cli.command,
+        Commands::Show { format: ReportFormat::Text, style: None, notes: false, no_notes: false, .. }
+    ));
+
+    // --no-notes で設定ファイルの notes = true を打ち消せる
+    let cli = Cli::parse_from(vec!["time-checker", "show", "--format", "markdown", "--no-notes"]);
+    assert!(matches!(cli.command, Commands::Show { notes: false, no_notes: true, .. }));
+    assert!(Cli::try_parse_from(vec!["time-checker", "show", "--notes", "--no-notes"]).is_err());
+
+    // report は show の別名
+    let args = vec!["time-checker", "report", "week", "--format", "markdown", "--style", "list", "--notes"];
+    let cli = Cli::parse_from(args);
+    match cli.command {
+        Commands::Show { period, format, style, notes, .. } => {
+            assert_eq!(period, "week");
+            assert_eq!(format, ReportFormat::Markdown);
+            assert_eq!(style, Some(MarkdownStyle::List));
+            assert!(notes);
+        }
+        _ => panic!("Expected Show command"),
+    }
+
+    assert!(Cli::try_parse_from(vec!["time-checker", "show", "--format", "html"]).is_err());
+}
+
+#[test]
+fn test_cli_global_json_flag() {
+    let cli = Cli::parse_from(vec!["time-checker", "status"]);
+    assert!(!cli.json);
+
+    // サブコマンドの前後どちらにも指定できる
+    let cli = Cli::parse_from(vec!["time-checker", "--json", "show", "week"]);
+    assert!(cli.json);
+    let cli = Cli::parse_from(vec!["time-checker", "list", "--json"]);
+    assert!(cli.json);
+}
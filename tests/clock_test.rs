@@ -0,0 +1,303 @@
+use chrono::{Duration, Local, TimeZone};
+use std::sync::Arc;
+use tempfile::tempdir;
+use time_checker::clock::{Clock, FixedClock};
+use time_checker::data::DataStore;
+use time_checker::error::TimeCheckerError;
+use time_checker::storage::Storage;
+use time_checker::tracker::{EntryEdit, Tracker};
+
+#[test]
+fn test_fixed_clock_set_and_advance() {
+    let clock = FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap());
+    assert_eq!(clock.now(), Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap());
+
+    // 時刻を進める
+    clock.advance(Duration::minutes(90));
+    assert_eq!(clock.now(), Local.with_ymd_and_hms(2025, 11, 5, 10, 30, 0).unwrap());
+
+    // 時刻を設定し直す
+    clock.set(Local.with_ymd_and_hms(2025, 11, 6, 8, 0, 0).unwrap());
+    assert_eq!(clock.today(), chrono::NaiveDate::from_ymd_opt(2025, 11, 6).unwrap());
+}
+
+#[test]
+fn test_tracker_with_fixed_clock() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("clock.json");
+
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let store = DataStore::with_clock(data_file, clock.clone());
+    let tracker = Tracker::with_clock(store, clock.clone());
+
+    // 9:00〜10:30 プログラミング、10:30〜11:00 会議
+    tracker.start_task("プログラミング".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(90));
+    tracker.start_task("会議".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(30));
+    tracker.stop_task().expect("タスクの停止に失敗");
+
+    let entries = tracker.store().load().expect("読み込みに失敗");
+    assert_eq!(entries[0].start, Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap());
+    assert_eq!(entries[1].end, Some(Local.with_ymd_and_hms(2025, 11, 5, 11, 0, 0).unwrap()));
+
+    // 時計の「今日」に基づいて集計される
+    let summary = tracker.get_today_summary().expect("サマリーの取得に失敗");
+    assert_eq!(summary.get("プログラミング").unwrap().as_secs(), 5400);
+    assert_eq!(summary.get("会議").unwrap().as_secs(), 1800);
+
+    let today_entries = tracker.store().get_today_entries().expect("取得に失敗");
+    assert_eq!(today_entries.len(), 2);
+}
+
+#[test]
+fn test_running_task_counts_until_clock_now() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("clock_running.json");
+
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 13, 0, 0).unwrap()));
+    let store = DataStore::with_clock(data_file, clock.clone());
+    let tracker = Tracker::with_clock(store, clock.clone());
+
+    tracker.start_task("実装作業".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(45));
+
+    // 進行中のタスクは時計の現在時刻まで計上される
+    let summary = tracker.get_today_summary().expect("サマリーの取得に失敗");
+    assert_eq!(summary.get("実装作業").unwrap().as_secs(), 2700);
+}
+
+#[test]
+fn test_start_and_stop_at_past_time() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("clock_at.json");
+
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 10, 0, 0).unwrap()));
+    let store = DataStore::with_clock(data_file, clock.clone());
+    let tracker = Tracker::with_clock(store, clock.clone());
+
+    tracker.start_task("プログラミング".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(60));
+
+    // 10:45 に会議を始めていたことにする（プログラミングは 10:45 で終了）
+    let at = Local.with_ymd_and_hms(2025, 11, 5, 10, 45, 0).unwrap();
+    tracker.start_task_at("会議".to_string(), None, at).expect("タスクの開始に失敗");
+
+    let entries = tracker.store().load().expect("読み込みに失敗");
+    assert_eq!(entries[0].end, Some(at));
+    assert_eq!(entries[1].start, at);
+
+    // 進行中のタスクの開始より前には停止できない
+    let result = tracker.stop_task_at(Local.with_ymd_and_hms(2025, 11, 5, 10, 30, 0).unwrap());
+    assert!(matches!(result, Err(TimeCheckerError::InvalidTimeRange(_))));
+
+    // 未来の時刻は指定できない
+    let result = tracker.stop_task_at(clock.now() + Duration::minutes(5));
+    assert!(matches!(result, Err(TimeCheckerError::InvalidTimeRange(_))));
+
+    tracker.stop_task_at(Local.with_ymd_and_hms(2025, 11, 5, 10, 55, 0).unwrap()).expect("タスクの停止に失敗");
+    let summary = tracker.get_today_summary().expect("サマリーの取得に失敗");
+    assert_eq!(summary.get("プログラミング").unwrap().as_secs(), 2700);
+    assert_eq!(summary.get("会議").unwrap().as_secs(), 600);
+}
+
+#[test]
+fn test_add_entry_rejects_overlap() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("clock_add.json");
+
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 18, 0, 0).unwrap()));
+    let store = DataStore::with_clock(data_file, clock.clone());
+    let tracker = Tracker::with_clock(store, clock.clone());
+
+    let added = tracker
+        .add_entry(
+            "会議".to_string(),
+            Some("週次定例".to_string()),
+            Local.with_ymd_and_hms(2025, 11, 5, 14, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2025, 11, 5, 15, 0, 0).unwrap(),
+        )
+        .expect("エントリの追加に失敗");
+    assert_eq!(added.id, 1);
+
+    // 時間が重なるエントリは追加できない
+    let result = tracker.add_entry(
+        "レビュー".to_string(),
+        None,
+        Local.with_ymd_and_hms(2025, 11, 5, 14, 30, 0).unwrap(),
+        Local.with_ymd_and_hms(2025, 11, 5, 16, 0, 0).unwrap(),
+    );
+    assert!(matches!(result, Err(TimeCheckerError::OverlappingEntry(1))));
+
+    // 追加済みのエントリより前から始めたタスクは重なる
+    let result = tracker.start_task_at(
+        "実装作業".to_string(),
+        None,
+        Local.with_ymd_and_hms(2025, 11, 5, 14, 30, 0).unwrap(),
+    );
+    assert!(matches!(result, Err(TimeCheckerError::OverlappingEntry(1))));
+
+    // 終了時刻とちょうど接するエントリは追加できる
+    tracker
+        .add_entry(
+            "レビュー".to_string(),
+            None,
+            Local.with_ymd_and_hms(2025, 11, 5, 15, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2025, 11, 5, 16, 0, 0).unwrap(),
+        )
+        .expect("エントリの追加に失敗");
+    assert_eq!(tracker.store().load().unwrap().len(), 2);
+}
+
+#[test]
+fn test_add_entry_rejects_future_end() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 6, 0, 0).unwrap()));
+    let store = DataStore::with_clock(dir.path().join("clock_future.json"), clock.clone());
+    let tracker = Tracker::with_clock(store, clock.clone());
+
+    // 終了時刻が未来のエントリは追加できない
+    let result = tracker.add_entry(
+        "未来".to_string(),
+        None,
+        Local.with_ymd_and_hms(2025, 11, 5, 23, 50, 0).unwrap(),
+        Local.with_ymd_and_hms(2025, 11, 5, 23, 59, 0).unwrap(),
+    );
+    assert!(matches!(result, Err(TimeCheckerError::InvalidTimeRange(_))));
+    assert!(tracker.store().load().unwrap().is_empty());
+
+    // 現在時刻までなら追加でき、その後も開始できる
+    tracker
+        .add_entry(
+            "朝の準備".to_string(),
+            None,
+            Local.with_ymd_and_hms(2025, 11, 5, 5, 30, 0).unwrap(),
+            Local.with_ymd_and_hms(2025, 11, 5, 6, 0, 0).unwrap(),
+        )
+        .expect("エントリの追加に失敗");
+    tracker.start_task("実装作業".to_string(), None).expect("開始に失敗");
+}
+
+#[test]
+fn test_edit_entry_rejects_future_times() {
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 12, 0, 0).unwrap()));
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let store = DataStore::with_clock(dir.path().join("clock_edit.json"), clock.clone());
+    let tracker = Tracker::with_clock(store, clock.clone());
+    let at = |h| Local.with_ymd_and_hms(2025, 11, 5, h, 0, 0).unwrap();
+
+    tracker.add_entry("設計".to_string(), None, at(9), at(10)).expect("エントリの追加に失敗");
+    tracker.start_task_at("実装".to_string(), None, at(11)).expect("開始に失敗");
+
+    // 終了時刻を未来にすると、これから開始するタスクと重なってしまう
+    let result = tracker.edit_entry(1, EntryEdit { end: Some(at(17)), ..Default::default() });
+    assert!(matches!(result, Err(TimeCheckerError::InvalidTimeRange(_))));
+
+    // 進行中のエントリの開始時刻も未来にはできない
+    let result = tracker.edit_entry(2, EntryEdit { start: Some(at(13)), ..Default::default() });
+    assert!(matches!(result, Err(TimeCheckerError::InvalidTimeRange(_))));
+
+    let entries = tracker.store().load().unwrap();
+    assert_eq!((entries[0].end, entries[1].start), (Some(at(10)), at(11)));
+
+    // 現在時刻までの変更はできる
+    tracker.edit_entry(2, EntryEdit { start: Some(at(12)), ..Default::default() }).expect("編集に失敗");
+    clock.advance(Duration::minutes(30));
+    tracker.start_task("レビュー".to_string(), None).expect("開始に失敗");
+}
+
+#[test]
+fn test_pause_and_resume_current_task() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let store = DataStore::with_clock(dir.path().join("pause.json"), clock.clone());
+    let tracker = Tracker::with_clock(store, clock.clone());
+
+    // 9:00〜9:40 作業、9:40〜9:55 中断、9:55〜 再開
+    tracker.start_task("資料作成".to_string(), Some("第2章".to_string())).unwrap();
+    clock.advance(Duration::minutes(40));
+    let paused = tracker.pause_task().expect("一時停止に失敗");
+    assert_eq!(paused.end, Some(clock.now()));
+    assert!(tracker.store().get_current_task().unwrap().is_none());
+    assert_eq!(tracker.get_paused_task().unwrap().map(|e| e.id), Some(1));
+
+    clock.advance(Duration::minutes(15));
+    let resumed = tracker.resume_task().expect("再開に失敗");
+    assert_eq!(resumed.task, "資料作成");
+    assert_eq!(resumed.note.as_deref(), Some("第2章"));
+    assert_eq!(resumed.resumed_from, Some(1));
+    assert!(tracker.get_paused_task().unwrap().is_none());
+
+    // 一時停止していた15分は作業時間に含めない
+    clock.advance(Duration::minutes(20));
+    assert_eq!(tracker.get_worked_time(&resumed).unwrap(), Duration::minutes(60));
+
+    // 一時停止中でなければ再開できない
+    assert!(matches!(tracker.resume_task(), Err(TimeCheckerError::NotPaused)));
+}
+
+#[test]
+fn test_stop_or_start_ends_paused_state() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let store = DataStore::with_clock(dir.path().join("pause.json"), clock.clone());
+    let tracker = Tracker::with_clock(store, clock.clone());
+
+    assert!(matches!(tracker.pause_task(), Err(TimeCheckerError::NoActiveTask)));
+
+    // 一時停止中に stop するとそのまま終了する
+    tracker.start_task("資料作成".to_string(), None).unwrap();
+    clock.advance(Duration::minutes(30));
+    tracker.pause_task().unwrap();
+    clock.advance(Duration::minutes(10));
+    tracker.stop_task().expect("停止に失敗");
+    assert!(tracker.get_paused_task().unwrap().is_none());
+    let entries = tracker.store().load().unwrap();
+    assert_eq!(entries[0].end, Some(Local.with_ymd_and_hms(2025, 11, 5, 9, 30, 0).unwrap()));
+
+    // 一時停止中に別のタスクを開始すると、一時停止していたタスクは再開できなくなる
+    tracker.start_task("会議".to_string(), None).unwrap();
+    clock.advance(Duration::minutes(30));
+    tracker.pause_task().unwrap();
+    tracker.start_task("レビュー".to_string(), None).unwrap();
+    clock.advance(Duration::minutes(10));
+    tracker.stop_task().unwrap();
+    assert!(tracker.get_paused_task().unwrap().is_none());
+    assert!(matches!(tracker.resume_task(), Err(TimeCheckerError::NotPaused)));
+}
+
+#[test]
+fn test_continue_previous_task() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let store = DataStore::with_clock(dir.path().join("continue.json"), clock.clone());
+    let tracker = Tracker::with_clock(store, clock.clone());
+
+    assert!(matches!(tracker.continue_task(1), Err(TimeCheckerError::NoRecentTask(1))));
+
+    // 設計（メモあり）→ 実装 → 設計 → 休憩
+    for (task, note) in [("設計", Some("API")), ("実装", None), ("設計", Some("DB")), ("休憩", None)] {
+        tracker.start_task(task.to_string(), note.map(str::to_string)).unwrap();
+        clock.advance(Duration::minutes(30));
+    }
+
+    // 進行中の「休憩」は候補に含まれず、同じタスク名は最新のエントリだけが残る
+    let recent: Vec<_> = tracker.recent_tasks(10).unwrap().into_iter().map(|e| e.task).collect();
+    assert_eq!(recent, vec!["設計", "実装"]);
+
+    let continued = tracker.continue_task(1).expect("タスクの開始に失敗");
+    assert_eq!(continued.task, "設計");
+    assert_eq!(continued.note.as_deref(), Some("DB"));
+    assert_eq!(continued.start, clock.now());
+
+    // 休憩は終了している
+    let entries = tracker.store().load().unwrap();
+    assert_eq!(entries[3].end, Some(clock.now()));
+
+    // 進行中の「設計」を除くと 休憩 → 実装 の順
+    clock.advance(Duration::minutes(30));
+    let continued = tracker.continue_task(2).expect("タスクの開始に失敗");
+    assert_eq!(continued.task, "実装");
+    assert!(matches!(tracker.continue_task(5), Err(TimeCheckerError::NoRecentTask(5))));
+    assert!(matches!(tracker.continue_task(0), Err(TimeCheckerError::NoRecentTask(0))));
+}
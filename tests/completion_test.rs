@@ -0,0 +1,74 @@
+use chrono::{Local, TimeZone};
+use clap::Parser;
+use time_checker::cli::{Cli, Commands, Shell};
+use std::ffi::OsString;
+use std::path::PathBuf;
+use time_checker::completion::{data_dir_arg, generate, task_suggestions};
+use time_checker::data::TimeEntry;
+
+fn entry(task: &str, hour: u32) -> TimeEntry {
+    TimeEntry {
+        task: task.to_string(),
+        start: Local.with_ymd_and_hms(2025, 11, 14, hour, 0, 0).unwrap(),
+        end: Some(Local.with_ymd_and_hms(2025, 11, 14, hour, 30, 0).unwrap()),
+        note: None,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_task_suggestions_most_recent_first() {
+    let entries = vec![
+        entry("設計", 9),
+        entry("実装作業", 10),
+        entry("設計", 11),
+        entry("コードレビュー", 12),
+    ];
+
+    // 重複を除き、最後に使ったタスクから順に並ぶ
+    let suggestions = task_suggestions(&entries);
+    assert_eq!(suggestions, vec!["コードレビュー", "設計", "実装作業"]);
+}
+
+#[test]
+fn test_task_suggestions_empty() {
+    assert!(task_suggestions(&[]).is_empty());
+}
+
+#[test]
+fn test_generate_completion_scripts() {
+    for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Powershell] {
+        let mut buf = Vec::new();
+        generate(shell, &mut buf).expect("補完スクリプトの生成に失敗");
+
+        let script = String::from_utf8(buf).expect("UTF-8ではありません");
+        assert!(script.contains("time-checker"), "{:?} のスクリプトにコマンド名がありません", shell);
+    }
+}
+
+#[test]
+fn test_cli_completion_command() {
+    let cli = Cli::parse_from(["time-checker", "completion", "zsh"]);
+
+    match cli.command {
+        Commands::Completion { shell } => assert_eq!(shell, Shell::Zsh),
+        _ => panic!("Expected Completion command"),
+    }
+
+    // 未対応のシェルはエラー
+    assert!(Cli::try_parse_from(["time-checker", "completion", "tcsh"]).is_err());
+}
+
+#[test]
+fn test_data_dir_arg_from_completion_args() {
+    let args = |line: &str| line.split(' ').map(OsString::from).collect::<Vec<_>>();
+
+    // 補完スクリプトは `time-checker -- <入力中のコマンドライン>` の形で呼び出す
+    let dir = data_dir_arg(args("time-checker -- time-checker --data-dir /tmp/other start 設"));
+    assert_eq!(dir, Some(PathBuf::from("/tmp/other")));
+    let dir = data_dir_arg(args("time-checker -- time-checker start --data-dir=/tmp/work 設"));
+    assert_eq!(dir, Some(PathBuf::from("/tmp/work")));
+
+    assert_eq!(data_dir_arg(args("time-checker -- time-checker start 設")), None);
+    assert_eq!(data_dir_arg(args("time-checker -- time-checker --data-dir")), None);
+}
@@ -0,0 +1,150 @@
+use std::path::PathBuf;
+use tempfile::tempdir;
+use time_checker::config::{self, Config, DataDirSource, StorageBackend};
+use time_checker::report::{HourFormat, MarkdownStyle};
+
+#[test]
+fn test_config_parse() {
+    let config = Config::parse(
+        r#"
+        [storage]
+        data_dir = "/srv/time-checker"
+        "#,
+    )
+    .expect("パースに失敗");
+
+    assert_eq!(config.storage.data_dir, Some(PathBuf::from("/srv/time-checker")));
+}
+
+#[test]
+fn test_config_parse_empty_and_invalid() {
+    // 空の設定はデフォルト値
+    assert_eq!(Config::parse("").expect("パースに失敗"), Config::default());
+
+    // 型が合わない設定はエラー
+    assert!(Config::parse("[storage]\ndata_dir = 1").is_err());
+}
+
+#[test]
+fn test_config_load_from_missing_file() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let config = Config::load_from(&dir.path().join("config.toml")).expect("読み込みに失敗");
+    assert_eq!(config, Config::default());
+}
+
+#[test]
+fn test_resolve_data_dir_priority() {
+    let config = Config::parse("[storage]\ndata_dir = \"/from/config\"").expect("パースに失敗");
+
+    // コマンドライン > 環境変数 > 設定ファイル
+    let location = config::resolve_data_dir(
+        Some(PathBuf::from("/from/flag")),
+        Some(PathBuf::from("/from/env")),
+        &config,
+    )
+    .expect("解決に失敗");
+    assert_eq!(location.dir, PathBuf::from("/from/flag"));
+    assert_eq!(location.source, DataDirSource::Flag);
+
+    let location = config::resolve_data_dir(None, Some(PathBuf::from("/from/env")), &config)
+        .expect("解決に失敗");
+    assert_eq!(location.dir, PathBuf::from("/from/env"));
+    assert_eq!(location.source, DataDirSource::Env);
+
+    let location = config::resolve_data_dir(None, None, &config).expect("解決に失敗");
+    assert_eq!(location.dir, PathBuf::from("/from/config"));
+    assert_eq!(location.source, DataDirSource::Config);
+    assert_eq!(location.data_file(), PathBuf::from("/from/config/data.json"));
+}
+
+#[test]
+fn test_resolve_data_dir_default() {
+    // 空の環境変数は指定なしとして扱う
+    let location = config::resolve_data_dir(None, Some(PathBuf::new()), &Config::default())
+        .expect("解決に失敗");
+    assert_eq!(location.source, DataDirSource::Default);
+    assert!(location.dir.ends_with("time-checker"));
+}
+
+#[test]
+fn test_migrate_legacy_data() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let legacy = dir.path().join(".time-checker");
+    let target = dir.path().join("share").join("time-checker");
+
+    std::fs::create_dir_all(&legacy).expect("ディレクトリ作成に失敗");
+    std::fs::write(legacy.join("data.json"), "[]").expect("ファイル作成に失敗");
+
+    let migrated = config::migrate_legacy_data(&legacy, &target).expect("移行に失敗");
+    assert!(migrated);
+    assert_eq!(std::fs::read_to_string(target.join("data.json")).unwrap(), "[]");
+    assert!(!legacy.exists());
+
+    // 2回目は何もしない
+    let migrated = config::migrate_legacy_data(&legacy, &target).expect("移行に失敗");
+    assert!(!migrated);
+}
+
+#[test]
+fn test_migrate_legacy_data_keeps_existing_target() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let legacy = dir.path().join("legacy");
+    let target = dir.path().join("target");
+
+    std::fs::create_dir_all(&legacy).expect("ディレクトリ作成に失敗");
+    std::fs::create_dir_all(&target).expect("ディレクトリ作成に失敗");
+    std::fs::write(legacy.join("data.json"), "[\"old\"]").expect("ファイル作成に失敗");
+    std::fs::write(target.join("data.json"), "[]").expect("ファイル作成に失敗");
+
+    // 移行先にデータがある場合は上書きしない
+    let migrated = config::migrate_legacy_data(&legacy, &target).expect("移行に失敗");
+    assert!(!migrated);
+    assert_eq!(std::fs::read_to_string(target.join("data.json")).unwrap(), "[]");
+    assert!(legacy.join("data.json").exists());
+}
+
+#[test]
+fn test_config_parse_exclude_tasks() {
+    let config = Config::parse(
+        r#"
+        [filters]
+        exclude_tasks = ["昼休憩", "休憩*", "/^MTG/"]
+        "#,
+    )
+    .expect("パースに失敗");
+
+    assert_eq!(config.filters.exclude_tasks, vec!["昼休憩", "休憩*", "/^MTG/"]);
+}
+
+#[test]
+fn test_config_storage_backend() {
+    assert_eq!(Config::default().storage.backend, StorageBackend::Json);
+
+    let config = Config::parse("[storage]\nbackend = \"sqlite\"").expect("パースに失敗");
+    assert_eq!(config.storage.backend, StorageBackend::Sqlite);
+
+    assert!(Config::parse("[storage]\nbackend = \"csv\"").is_err());
+}
+
+#[test]
+fn test_config_report_section() {
+    let config = Config::parse(
+        r#"
+        [report]
+        style = "list"
+        notes = true
+        heading = "日報 {range}"
+        hours = "decimal"
+        "#,
+    )
+    .expect("パースに失敗");
+
+    assert_eq!(config.report.style, MarkdownStyle::List);
+    assert!(config.report.notes);
+    assert_eq!(config.report.heading, "日報 {range}");
+    assert_eq!(config.report.hours, HourFormat::Decimal);
+    // 指定していない項目はデフォルト値
+    assert_eq!(config.report.heading_level, 2);
+
+    assert!(Config::parse("[report]\nhours = \"minutes\"").is_err());
+}
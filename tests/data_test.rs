@@ -1,5 +1,7 @@
 use chrono::{Local, TimeZone};
-use time_checker::data::{TimeEntry, DataStore};
+use time_checker::data::{parse_data, parse_tag, TimeEntry, DataStore, DATA_VERSION};
+use time_checker::error::TimeCheckerError;
+use time_checker::storage::Storage;
 use tempfile::tempdir;
 
 #[test]
@@ -13,6 +15,7 @@ fn test_timeentry_serialization_with_all_fields() {
         start: start_time,
         end: Some(end_time),
         note: Some("Rust実装".to_string()),
+        ..Default::default()
     };
 
     // JSONにシリアライズ
@@ -33,6 +36,7 @@ fn test_timeentry_serialization_without_optional_fields() {
         start: start_time,
         end: None,
         note: None,
+        ..Default::default()
     };
 
     let json = serde_json::to_string(&entry).expect("シリアライズに失敗");
@@ -68,6 +72,7 @@ fn test_timeentry_roundtrip() {
         start: start_time,
         end: Some(end_time),
         note: Some("設計書更新".to_string()),
+        ..Default::default()
     };
 
     let json = serde_json::to_string(&original).expect("シリアライズに失敗");
@@ -94,6 +99,7 @@ fn test_datastore_save_and_load() {
             start: start_time,
             end: Some(end_time),
             note: None,
+            ..Default::default()
         },
     ];
 
@@ -148,12 +154,14 @@ fn test_datastore_get_current_task() {
             start: start_time1,
             end: Some(end_time1),
             note: None,
+            ..Default::default()
         },
         TimeEntry {
             task: "進行中タスク".to_string(),
             start: start_time2,
             end: None,
             note: None,
+            ..Default::default()
         },
     ];
 
@@ -181,6 +189,7 @@ fn test_datastore_get_current_task_none() {
             start: start_time,
             end: Some(end_time),
             note: None,
+            ..Default::default()
         },
     ];
 
@@ -210,12 +219,14 @@ fn test_datastore_get_today_entries() {
             start: Local.from_local_datetime(&yesterday).unwrap(),
             end: Some(Local.from_local_datetime(&yesterday.checked_add_signed(chrono::Duration::hours(1)).unwrap()).unwrap()),
             note: None,
+            ..Default::default()
         },
         TimeEntry {
             task: "今日のタスク".to_string(),
             start: Local.from_local_datetime(&today_start).unwrap(),
             end: Some(Local.from_local_datetime(&today_end).unwrap()),
             note: None,
+            ..Default::default()
         },
     ];
 
@@ -227,3 +238,281 @@ fn test_datastore_get_today_entries() {
     assert_eq!(today_entries.len(), 1);
     assert_eq!(today_entries[0].task, "今日のタスク");
 }
+
+#[test]
+fn test_timeentry_split_by_day() {
+    let entry = TimeEntry {
+        task: "夜勤".to_string(),
+        start: Local.with_ymd_and_hms(2025, 11, 4, 22, 30, 0).unwrap(),
+        end: Some(Local.with_ymd_and_hms(2025, 11, 6, 1, 0, 0).unwrap()),
+        note: None,
+        ..Default::default()
+    };
+
+    let now = Local.with_ymd_and_hms(2025, 11, 10, 0, 0, 0).unwrap();
+    let days = entry.split_by_day(now);
+
+    // 3日にまたがるエントリは0時で3つに分割される
+    assert_eq!(days.len(), 3);
+    assert_eq!(days[0].1.num_minutes(), 90);
+    assert_eq!(days[1].1.num_minutes(), 24 * 60);
+    assert_eq!(days[2].1.num_minutes(), 60);
+}
+
+#[test]
+fn test_datastore_get_entries_in_range_includes_overnight_entry() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("overnight.json");
+    let store = DataStore::new(data_file);
+
+    let entries = vec![TimeEntry {
+        task: "デプロイ".to_string(),
+        start: Local.with_ymd_and_hms(2025, 11, 4, 23, 0, 0).unwrap(),
+        end: Some(Local.with_ymd_and_hms(2025, 11, 5, 2, 0, 0).unwrap()),
+        note: None,
+        ..Default::default()
+    }];
+
+    store.save(&entries).expect("保存に失敗");
+
+    // 前日に開始したエントリも翌日の範囲に含まれる
+    use time_checker::period::DateRange;
+    let next_day = chrono::NaiveDate::from_ymd_opt(2025, 11, 5).unwrap();
+    let found = store
+        .get_entries_in_range(DateRange::single(next_day))
+        .expect("取得に失敗");
+    assert_eq!(found.len(), 1);
+
+    let later_day = chrono::NaiveDate::from_ymd_opt(2025, 11, 6).unwrap();
+    let found = store
+        .get_entries_in_range(DateRange::single(later_day))
+        .expect("取得に失敗");
+    assert!(found.is_empty());
+}
+
+#[test]
+fn test_datastore_backfills_ids_for_legacy_file() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("legacy.json");
+    let store = DataStore::new(data_file);
+
+    // IDのない古い形式のファイル
+    let json = r#"[
+        {"task": "タスク1", "start": "2025-11-14T09:00:00+09:00", "end": "2025-11-14T10:00:00+09:00"},
+        {"task": "タスク2", "start": "2025-11-14T10:00:00+09:00", "end": null}
+    ]"#;
+    std::fs::write(store.data_file(), json).expect("ファイル作成に失敗");
+
+    // 読み込むたびに同じIDが割り当てられる
+    let loaded = store.load().expect("読み込みに失敗");
+    assert_eq!(loaded[0].id, 1);
+    assert_eq!(loaded[1].id, 2);
+    assert_eq!(store.load().expect("読み込みに失敗"), loaded);
+
+    // 保存するとIDがファイルに書き込まれる
+    store.save(&loaded).expect("保存に失敗");
+    let content = std::fs::read_to_string(store.data_file()).expect("読み込みに失敗");
+    assert!(content.contains("\"id\": 2"));
+}
+
+#[test]
+fn test_datastore_save_assigns_missing_ids() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("assign.json");
+    let store = DataStore::new(data_file);
+
+    let start_time = Local.with_ymd_and_hms(2025, 11, 14, 9, 0, 0).unwrap();
+    let entries = vec![
+        TimeEntry {
+            id: 5,
+            task: "既存".to_string(),
+            start: start_time,
+            ..Default::default()
+        },
+        TimeEntry {
+            task: "新規".to_string(),
+            start: start_time,
+            ..Default::default()
+        },
+    ];
+
+    store.save(&entries).expect("保存に失敗");
+
+    // 既存のIDはそのまま、未採番のものには続きの番号が振られる
+    let new_entry = store.get_entry(6).expect("取得に失敗").expect("エントリがありません");
+    assert_eq!(new_entry.task, "新規");
+    assert_eq!(store.get_entry(5).expect("取得に失敗").unwrap().task, "既存");
+    assert!(store.get_entry(7).expect("取得に失敗").is_none());
+}
+
+#[test]
+fn test_timeentry_conflicts_with() {
+    let entry = |h1: u32, h2: Option<u32>| TimeEntry {
+        task: "作業".to_string(),
+        start: Local.with_ymd_and_hms(2025, 11, 14, h1, 0, 0).unwrap(),
+        end: h2.map(|h| Local.with_ymd_and_hms(2025, 11, 14, h, 0, 0).unwrap()),
+        ..Default::default()
+    };
+
+    // 重なっている
+    assert!(entry(9, Some(11)).conflicts_with(&entry(10, Some(12))));
+    // 端点が接しているだけなら重ならない
+    assert!(!entry(9, Some(10)).conflicts_with(&entry(10, Some(11))));
+    // 進行中のエントリは終わりがないものとして扱う
+    assert!(entry(9, None).conflicts_with(&entry(15, Some(16))));
+    assert!(!entry(12, None).conflicts_with(&entry(9, Some(10))));
+}
+
+#[test]
+fn test_datastore_save_keeps_backup_of_previous_file() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let store = DataStore::new(dir.path().join("data.json"));
+    let start_time = Local.with_ymd_and_hms(2025, 11, 14, 9, 0, 0).unwrap();
+
+    let first = vec![TimeEntry {
+        task: "タスク1".to_string(),
+        start: start_time,
+        ..Default::default()
+    }];
+    store.save(&first).expect("保存に失敗");
+
+    // 最初の保存ではバックアップは作られない
+    assert!(!store.backup_file().exists());
+
+    let mut second = store.load().expect("読み込みに失敗");
+    second.push(TimeEntry {
+        task: "タスク2".to_string(),
+        start: start_time,
+        ..Default::default()
+    });
+    store.save(&second).expect("保存に失敗");
+
+    // バックアップには直前の内容が残っている
+    assert_eq!(store.backup_file(), dir.path().join("data.json.bak"));
+    let backup = DataStore::new(store.backup_file()).load().expect("バックアップの読み込みに失敗");
+    assert_eq!(backup.len(), 1);
+    assert_eq!(backup[0].task, "タスク1");
+    assert_eq!(store.load().expect("読み込みに失敗").len(), 2);
+
+    // 一時ファイルは残らない
+    let files: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(files.len(), 2, "{:?}", files);
+}
+
+#[test]
+fn test_datastore_corrupted_file_points_to_backup() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let store = DataStore::new(dir.path().join("data.json"));
+
+    store.save(&[]).expect("保存に失敗");
+    store.save(&[]).expect("保存に失敗");
+
+    // 書き込み途中で壊れたファイルを再現
+    std::fs::write(store.data_file(), "[{\"task\": \"途中").expect("ファイル作成に失敗");
+
+    let error = store.load().expect_err("壊れたファイルが読み込めてしまった");
+    assert!(error.to_string().contains("data.json.bak"));
+}
+
+#[test]
+fn test_datastore_saves_versioned_envelope() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let store = DataStore::new(dir.path().join("data.json"));
+
+    let entries = vec![TimeEntry {
+        task: "タスク1".to_string(),
+        start: Local.with_ymd_and_hms(2025, 11, 14, 9, 0, 0).unwrap(),
+        ..Default::default()
+    }];
+    store.save(&entries).expect("保存に失敗");
+
+    let content = std::fs::read_to_string(store.data_file()).expect("読み込みに失敗");
+    let document: serde_json::Value = serde_json::from_str(&content).expect("JSONではない");
+    assert_eq!(document["version"], DATA_VERSION);
+    assert_eq!(document["entries"][0]["task"], "タスク1");
+    assert_eq!(document["entries"][0]["id"], 1);
+}
+
+#[test]
+fn test_parse_data_migrates_old_versions() {
+    // バージョン1（配列のみ、IDなし）
+    let v1 = r#"[
+        {"task": "タスク1", "start": "2025-11-14T09:00:00+09:00", "end": "2025-11-14T10:00:00+09:00"},
+        {"id": 7, "task": "タスク2", "start": "2025-11-14T10:00:00+09:00", "end": null}
+    ]"#;
+    let entries = parse_data(v1).expect("パースに失敗");
+    assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), vec![8, 7]);
+
+    // バージョン2（一時停止の情報なし）
+    let v2 = r#"{"version": 2, "entries": [
+        {"id": 1, "task": "タスク1", "start": "2025-11-14T09:00:00+09:00", "end": "2025-11-14T10:00:00+09:00"}
+    ]}"#;
+    let entries = parse_data(v2).expect("パースに失敗");
+    assert!(!entries[0].paused);
+    assert_eq!(entries[0].resumed_from, None);
+
+    // バージョン4（次に採番するIDなし）は最大のID + 1 から採番する
+    let v4 = r#"{"version": 4, "entries": [
+        {"id": 3, "task": "タスク1", "start": "2025-11-14T09:00:00+09:00", "end": null}
+    ]}"#;
+    assert_eq!(parse_data(v4).expect("パースに失敗").next_id(), 4);
+
+    // 現在のバージョン
+    let current = format!(r#"{{"version": {}, "next_id": 10, "entries": []}}"#, DATA_VERSION);
+    let entries = parse_data(&current).expect("パースに失敗");
+    assert!(entries.is_empty());
+    assert_eq!(entries.next_id(), 10);
+}
+
+#[test]
+fn test_parse_data_rejects_newer_version() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let store = DataStore::new(dir.path().join("data.json"));
+
+    let newer = format!(r#"{{"version": {}, "entries": [], "projects": []}}"#, DATA_VERSION + 1);
+    std::fs::write(store.data_file(), &newer).expect("ファイル作成に失敗");
+
+    let error = store.load().expect_err("新しい形式が読み込めてしまった");
+    assert!(matches!(error, TimeCheckerError::UnsupportedDataVersion(v) if v == DATA_VERSION + 1));
+    assert!(error.to_string().contains("更新してください"));
+
+    // 読み込めないファイルを上書きしないよう、更新処理も失敗する
+    let result = store.transaction(|entries| {
+        entries.clear();
+        Ok(())
+    });
+    assert!(result.is_err());
+    assert_eq!(std::fs::read_to_string(store.data_file()).unwrap(), newer);
+
+    assert!(parse_data(r#"{"entries": []}"#).is_err());
+    assert!(parse_data(r#""text""#).is_err());
+}
+
+#[test]
+fn test_parse_data_rejects_missing_entries() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let store = DataStore::new(dir.path().join("data.json"));
+
+    let content = format!(r#"{{"version": {}, "next_id": 3, "items": []}}"#, DATA_VERSION);
+    std::fs::write(store.data_file(), &content).expect("ファイル作成に失敗");
+
+    let error = store.load().expect_err("エントリの一覧がないのに読み込めてしまった");
+    assert!(matches!(error, TimeCheckerError::DataLoadError(ref msg) if msg.contains("エントリの一覧がありません")));
+
+    // 空の一覧として上書きしない
+    assert!(store.transaction(|_| Ok(())).is_err());
+    assert_eq!(std::fs::read_to_string(store.data_file()).unwrap(), content);
+}
+
+#[test]
+fn test_parse_tag() {
+    assert_eq!(parse_tag("+backend").unwrap(), "backend");
+    assert_eq!(parse_tag("バックエンド").unwrap(), "バックエンド");
+
+    for invalid in ["+", "", "a b", "a,b", "++a"] {
+        assert!(matches!(parse_tag(invalid), Err(TimeCheckerError::InvalidTag(_))), "{}", invalid);
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use std::time::Duration;
+use time_checker::data::TimeEntry;
+use time_checker::export::{self, Granularity};
+use time_checker::period::DateRange;
+use time_checker::storage::MemoryStorage;
+use time_checker::tracker::Tracker;
+
+fn date(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 11, d).unwrap()
+}
+
+fn to_string(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes).expect("UTF-8 ではない")
+}
+
+#[test]
+fn test_csv_field_quoting() {
+    assert_eq!(export::csv_field("設計レビュー"), "設計レビュー");
+    assert_eq!(export::csv_field("資料作成、確認"), "資料作成、確認");
+    assert_eq!(export::csv_field("A社,B社"), "\"A社,B社\"");
+    assert_eq!(export::csv_field("「\"仮\"」の件"), "\"「\"\"仮\"\"」の件\"");
+    assert_eq!(export::csv_field("1行目\n2行目"), "\"1行目\n2行目\"");
+    assert_eq!(export::csv_field(""), "");
+}
+
+#[test]
+fn test_write_entries_csv() {
+    let at = |d, h, m| Local.with_ymd_and_hms(2025, 11, d, h, m, 0).unwrap();
+    let entries = vec![
+        TimeEntry {
+            id: 1,
+            task: "会議".to_string(),
+            start: at(5, 9, 0),
+            end: Some(at(5, 10, 30)),
+            note: Some("週次定例, 議事録あり".to_string()),
+            tags: vec!["team".to_string(), "weekly".to_string()],
+            ..Default::default()
+        },
+        TimeEntry {
+            id: 2,
+            task: "実装".to_string(),
+            start: at(5, 11, 0),
+            end: None,
+            ..Default::default()
+        },
+    ];
+
+    let mut out = Vec::new();
+    export::write_entries_csv(&mut out, &entries, at(5, 11, 45)).expect("書き出しに失敗");
+
+    assert_eq!(
+        to_string(out),
+        "id,task,start,end,duration,note,tags\r\n\
+         1,会議,2025-11-05 09:00:00,2025-11-05 10:30:00,1:30:00,\"週次定例, 議事録あり\",team weekly\r\n\
+         2,実装,2025-11-05 11:00:00,,0:45:00,,\r\n"
+    );
+}
+
+#[test]
+fn test_summary_aggregation() {
+    let tracker = Tracker::new(MemoryStorage::new());
+    let at = |d, h, m| Local.with_ymd_and_hms(2025, 11, d, h, m, 0).unwrap();
+    // 2025-11-02 は日曜、11-03 は月曜
+    tracker.add_entry("設計".to_string(), None, at(2, 9, 0), at(2, 10, 0)).unwrap();
+    tracker.add_entry("設計".to_string(), None, at(3, 9, 0), at(3, 9, 30)).unwrap();
+    tracker.add_entry("実装".to_string(), None, at(4, 13, 0), at(4, 15, 15)).unwrap();
+
+    let range = DateRange::new(date(1), date(30));
+    let daily = tracker.get_daily_summary(range).expect("集計に失敗");
+
+    let days = export::aggregate(&daily, range, Granularity::Day);
+    assert_eq!(days.len(), 3);
+    assert_eq!(days[0].range, DateRange::single(date(2)));
+
+    // 週の区切りは月曜で、期間の外にはみ出さない
+    let weeks = export::aggregate(&daily, range, Granularity::Week);
+    assert_eq!(weeks.len(), 3);
+    assert_eq!(weeks[0].range, DateRange::new(date(1), date(2)));
+    assert_eq!((weeks[1].task.as_str(), weeks[1].range), ("実装", DateRange::new(date(3), date(9))));
+    assert_eq!((weeks[2].task.as_str(), weeks[2].duration), ("設計", Duration::from_secs(30 * 60)));
+
+    let total = export::aggregate(&daily, range, Granularity::Month);
+    assert_eq!(total.len(), 2);
+    assert_eq!(total[1].range, range);
+    assert_eq!(total[1].duration, Duration::from_secs(90 * 60));
+
+    let mut out = Vec::new();
+    export::write_summary_csv(&mut out, &total).expect("書き出しに失敗");
+    assert_eq!(
+        to_string(out),
+        "from,to,task,duration,hours\r\n\
+         2025-11-01,2025-11-30,実装,2:15:00,2.25\r\n\
+         2025-11-01,2025-11-30,設計,1:30:00,1.50\r\n"
+    );
+}
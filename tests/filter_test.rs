@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use time_checker::filter::{split_patterns, TaskFilter, TaskPattern};
+use time_checker::summary::Summary;
+
+#[test]
+fn test_task_pattern_exact() {
+    let pattern: TaskPattern = "昼休憩".parse().expect("パースに失敗");
+    assert!(pattern.matches("昼休憩"));
+    assert!(!pattern.matches("昼休憩2"));
+}
+
+#[test]
+fn test_task_pattern_prefix() {
+    let pattern: TaskPattern = "休憩*".parse().expect("パースに失敗");
+    assert!(pattern.matches("休憩"));
+    assert!(pattern.matches("休憩（午後）"));
+    assert!(!pattern.matches("昼休憩"));
+}
+
+#[test]
+fn test_task_pattern_regex() {
+    let pattern: TaskPattern = r"/^MTG-\d+$/".parse().expect("パースに失敗");
+    assert!(pattern.matches("MTG-12"));
+    assert!(!pattern.matches("MTG-ab"));
+
+    // 不正な正規表現はエラー
+    assert!("/(/".parse::<TaskPattern>().is_err());
+    assert!("".parse::<TaskPattern>().is_err());
+}
+
+#[test]
+fn test_task_filter_matches_any_pattern() {
+    let filter = TaskFilter::parse(&["昼休憩", "コーヒー*"]).expect("パースに失敗");
+    assert!(filter.matches("昼休憩"));
+    assert!(filter.matches("コーヒーブレイク"));
+    assert!(!filter.matches("実装作業"));
+
+    assert!(TaskFilter::none().is_empty());
+    assert!(!TaskFilter::none().matches("昼休憩"));
+}
+
+#[test]
+fn test_summary_partitions_excluded_tasks() {
+    let mut totals = HashMap::new();
+    totals.insert("実装作業".to_string(), Duration::from_secs(6 * 3600));
+    totals.insert("昼休憩".to_string(), Duration::from_secs(3600));
+    totals.insert("コーヒーブレイク".to_string(), Duration::from_secs(900));
+
+    let filter = TaskFilter::parse(&["昼休憩", "コーヒー*"]).expect("パースに失敗");
+    let summary = Summary::new(totals, &filter);
+
+    assert_eq!(summary.included.len(), 1);
+    assert_eq!(summary.excluded.len(), 2);
+    assert_eq!(summary.subtotal().as_secs(), 6 * 3600);
+    assert_eq!(summary.excluded_total().as_secs(), 4500);
+    assert_eq!(summary.total().as_secs(), 6 * 3600 + 4500);
+}
+
+#[test]
+fn test_split_patterns_keeps_commas_in_regex() {
+    assert_eq!(split_patterns("昼休憩,打ち合わせ"), vec!["昼休憩", "打ち合わせ"]);
+    assert_eq!(split_patterns("/^(a,b)$/,休憩*"), vec!["/^(a,b)$/", "休憩*"]);
+    assert_eq!(split_patterns(r"/x{1,3}/, /a\/b,c/"), vec!["/x{1,3}/", r" /a\/b,c/"]);
+
+    // 複数回指定した引数もまとめてフィルタにする
+    let filter = TaskFilter::parse_args(&["/^MTG-\\d{1,3}$/", "昼休憩,/^(私用,通院)$/"]).expect("パースに失敗");
+    assert_eq!(filter.patterns().len(), 3);
+    assert!(filter.matches("MTG-12"));
+    assert!(filter.matches("私用,通院"));
+    assert!(!filter.matches("通院"));
+    assert!(!filter.matches("MTG-1234"));
+}
@@ -0,0 +1,153 @@
+use chrono::{Duration, Local, TimeZone};
+use std::sync::Arc;
+use tempfile::tempdir;
+use time_checker::clock::FixedClock;
+use time_checker::data::DataStore;
+use time_checker::error::TimeCheckerError;
+use time_checker::history::{OperationHistory, OperationKind, UNDO_FILE_NAME};
+use time_checker::storage::{MemoryStorage, Storage};
+use time_checker::tracker::{EntryEdit, Tracker};
+
+fn memory_tracker(dir: &std::path::Path) -> (Tracker<MemoryStorage>, Arc<FixedClock>) {
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let history = OperationHistory::new(dir.join(UNDO_FILE_NAME));
+    let tracker = Tracker::with_clock(MemoryStorage::new(), clock.clone()).with_history(history);
+    (tracker, clock)
+}
+
+#[test]
+fn test_undo_start_restores_previous_task() {
+    let dir = tempdir().unwrap();
+    let (tracker, clock) = memory_tracker(dir.path());
+
+    tracker.start_task("設計".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(30));
+    tracker.start_task("実装".to_string(), None).expect("タスクの開始に失敗");
+
+    let undone = tracker.undo(1).expect("取り消しに失敗");
+    assert_eq!(undone.len(), 1);
+    assert_eq!(undone[0].kind, OperationKind::Start);
+
+    // 自動的に終了された「設計」が進行中に戻る
+    let entries = tracker.store().load().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].task, "設計");
+    assert_eq!(entries[0].end, None);
+}
+
+#[test]
+fn test_undo_and_redo_multiple_operations() {
+    let dir = tempdir().unwrap();
+    let (tracker, clock) = memory_tracker(dir.path());
+
+    tracker.start_task("設計".to_string(), None).unwrap();
+    clock.advance(Duration::minutes(30));
+    tracker.stop_task().unwrap();
+    tracker
+        .edit_entry(1, EntryEdit { task: Some("レビュー".to_string()), ..Default::default() })
+        .unwrap();
+    let after_edit = tracker.store().load().unwrap();
+
+    let undone = tracker.undo(2).expect("取り消しに失敗");
+    let kinds: Vec<_> = undone.iter().map(|op| op.kind).collect();
+    assert_eq!(kinds, vec![OperationKind::Edit, OperationKind::Stop]);
+
+    let entries = tracker.store().load().unwrap();
+    assert_eq!(entries[0].task, "設計");
+    assert_eq!(entries[0].end, None);
+
+    let redone = tracker.redo(5).expect("やり直しに失敗");
+    assert_eq!(redone.len(), 2);
+    assert_eq!(tracker.store().load().unwrap(), after_edit);
+
+    assert!(matches!(tracker.redo(1), Err(TimeCheckerError::NothingToRedo)));
+}
+
+#[test]
+fn test_undo_delete_and_new_operation_clears_redo() {
+    let dir = tempdir().unwrap();
+    let (tracker, clock) = memory_tracker(dir.path());
+
+    tracker.start_task("設計".to_string(), Some("メモ".to_string())).unwrap();
+    clock.advance(Duration::minutes(30));
+    tracker.stop_task().unwrap();
+    let before_delete = tracker.store().load().unwrap();
+
+    tracker.delete_entry(1).unwrap();
+    tracker.undo(1).expect("取り消しに失敗");
+    assert_eq!(tracker.store().load().unwrap(), before_delete);
+
+    // 新しい操作をするとやり直せる操作は破棄される
+    tracker.start_task("実装".to_string(), None).unwrap();
+    assert!(matches!(tracker.redo(1), Err(TimeCheckerError::NothingToRedo)));
+}
+
+#[test]
+fn test_undo_refuses_entry_changed_outside_history() {
+    let dir = tempdir().unwrap();
+    let (tracker, clock) = memory_tracker(dir.path());
+
+    tracker.start_task("設計".to_string(), None).unwrap();
+    clock.advance(Duration::minutes(30));
+
+    // 履歴を通さずに同じエントリを変更する
+    let mut entries = tracker.store().load().unwrap();
+    entries[0].task = "別の変更".to_string();
+    tracker.store().save(&entries).unwrap();
+
+    assert!(matches!(tracker.undo(1), Err(TimeCheckerError::HistoryConflict(1))));
+    assert_eq!(tracker.store().load().unwrap(), entries);
+
+    // 取り消せなかった操作は履歴に残る
+    let stacks = tracker.history().unwrap().load().unwrap();
+    assert_eq!(stacks.undo.len(), 1);
+    assert!(stacks.redo.is_empty());
+}
+
+#[test]
+fn test_history_persists_next_to_data_file() {
+    let dir = tempdir().unwrap();
+    let data_file = dir.path().join("data.json");
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let open = || {
+        Tracker::with_clock(DataStore::with_clock(data_file.clone(), clock.clone()), clock.clone())
+            .with_history(OperationHistory::new(dir.path().join(UNDO_FILE_NAME)))
+    };
+
+    open().start_task("設計".to_string(), None).unwrap();
+    clock.advance(Duration::minutes(10));
+    open().stop_task().unwrap();
+    assert!(dir.path().join(UNDO_FILE_NAME).exists());
+
+    // 別のインスタンス（別のコマンド実行）からも取り消せる
+    let undone = open().undo(1).expect("取り消しに失敗");
+    assert_eq!(undone[0].kind, OperationKind::Stop);
+    assert_eq!(open().store().load().unwrap()[0].end, None);
+}
+
+#[test]
+fn test_undo_without_history_or_operations() {
+    let tracker = Tracker::new(MemoryStorage::new());
+    assert!(matches!(tracker.undo(1), Err(TimeCheckerError::NothingToUndo)));
+
+    let dir = tempdir().unwrap();
+    let (tracker, _) = memory_tracker(dir.path());
+    assert!(matches!(tracker.undo(1), Err(TimeCheckerError::NothingToUndo)));
+    assert!(matches!(tracker.redo(1), Err(TimeCheckerError::NothingToRedo)));
+}
+
+#[test]
+fn test_history_limit_drops_oldest_operations() {
+    let dir = tempdir().unwrap();
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let history = OperationHistory::new(dir.path().join(UNDO_FILE_NAME)).with_limit(2);
+    let tracker = Tracker::with_clock(MemoryStorage::new(), clock.clone()).with_history(history);
+
+    for task in ["設計", "実装", "テスト"] {
+        tracker.start_task(task.to_string(), None).unwrap();
+        clock.advance(Duration::minutes(10));
+    }
+
+    assert_eq!(tracker.undo(5).unwrap().len(), 2);
+    assert_eq!(tracker.store().load().unwrap().len(), 1);
+}
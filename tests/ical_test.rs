@@ -0,0 +1,307 @@
+use chrono::{Local, NaiveDate, TimeZone, Utc};
+use std::sync::Arc;
+use tempfile::tempdir;
+use time_checker::clock::FixedClock;
+use time_checker::data::TimeEntry;
+use time_checker::error::TimeCheckerError;
+use time_checker::ical;
+use time_checker::period::DateRange;
+use time_checker::storage::{MemoryStorage, Storage};
+use time_checker::tracker::{SkipReason, Tracker};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn write_line(line: &str) -> String {
+    let mut out = Vec::new();
+    ical::write_line(&mut out, line).expect("書き出しに失敗");
+    String::from_utf8(out).expect("UTF-8 ではない")
+}
+
+#[test]
+fn test_escape_text() {
+    assert_eq!(ical::escape_text("設計レビュー"), "設計レビュー");
+    assert_eq!(ical::escape_text("A社,B社; 確認"), r"A社\,B社\; 確認");
+    assert_eq!(ical::escape_text("C:\\work"), "C:\\\\work");
+    assert_eq!(ical::escape_text("1行目\r\n2行目\n3行目"), "1行目\\n2行目\\n3行目");
+}
+
+#[test]
+fn test_write_line_folds_long_lines() {
+    assert_eq!(write_line("SUMMARY:会議"), "SUMMARY:会議\r\n");
+
+    let line = format!("SUMMARY:{}", "あ".repeat(60));
+    let folded = write_line(&line);
+    let lines: Vec<_> = folded.trim_end_matches("\r\n").split("\r\n").collect();
+
+    // 75バイト以内で、マルチバイト文字の途中では折り返さない
+    assert!(lines.len() > 1);
+    assert!(lines.iter().all(|l| l.len() <= 75));
+    assert!(lines[1..].iter().all(|l| l.starts_with(' ')));
+    assert_eq!(folded.replace("\r\n ", "").trim_end(), line);
+}
+
+#[test]
+fn test_write_calendar() {
+    let at = |h, m| Utc.with_ymd_and_hms(2025, 11, 5, h, m, 0).unwrap().with_timezone(&Local);
+    let entries = vec![
+        TimeEntry {
+            id: 7,
+            task: "会議".to_string(),
+            start: at(0, 0),
+            end: Some(at(1, 30)),
+            note: Some("週次定例, 議事録あり".to_string()),
+            tags: vec!["team".to_string()],
+            ..Default::default()
+        },
+        TimeEntry {
+            id: 8,
+            task: "実装".to_string(),
+            start: at(2, 0),
+            end: None,
+            ..Default::default()
+        },
+    ];
+
+    let mut out = Vec::new();
+    ical::write_calendar(&mut out, &entries, "0123456789abcdef", at(2, 30)).expect("書き出しに失敗");
+    let calendar = String::from_utf8(out).unwrap();
+
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    assert!(calendar.contains(
+        "BEGIN:VEVENT\r\n\
+         UID:entry-0123456789abcdef-7@time-checker\r\n\
+         DTSTAMP:20251105T023000Z\r\n\
+         DTSTART:20251105T000000Z\r\n\
+         DTEND:20251105T013000Z\r\n\
+         SUMMARY:会議\r\n\
+         DESCRIPTION:週次定例\\, 議事録あり\r\n\
+         CATEGORIES:team\r\n"
+    ));
+
+    // 進行中のエントリは含めない
+    assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+    assert!(!calendar.contains("-8@"));
+}
+
+#[test]
+fn test_entry_uid_is_stable() {
+    let entry = TimeEntry { id: 42, task: "会議".to_string(), ..Default::default() };
+    let renamed = TimeEntry { task: "定例".to_string(), start: Local::now(), ..entry.clone() };
+    assert_eq!(ical::entry_uid(&entry, "work"), "entry-work-42@time-checker");
+    assert_eq!(ical::entry_uid(&entry, "work"), ical::entry_uid(&renamed, "work"));
+
+    // データディレクトリの識別子は保存され、ディレクトリごとに異なる
+    let work = tempdir().expect("一時ディレクトリの作成に失敗");
+    let personal = tempdir().expect("一時ディレクトリの作成に失敗");
+    let id = ical::calendar_id(work.path()).expect("識別子の作成に失敗");
+    assert!(work.path().join(ical::CALENDAR_ID_FILE_NAME).exists());
+    assert_eq!(ical::calendar_id(work.path()).unwrap(), id);
+    assert_ne!(ical::calendar_id(personal.path()).unwrap(), id);
+}
+
+#[test]
+fn test_parse_calendar() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:a@example.com\r\n\
+                   DTSTART:20251105T000000Z\r\n\
+                   DTEND:20251105T013000Z\r\n\
+                   SUMMARY:設計\r\n \
+                   レビュー\r\n\
+                   DESCRIPTION:A社\\, B社\\n資料あり\r\n\
+                   CATEGORIES:review,team\r\n\
+                   BEGIN:VALARM\r\n\
+                   DESCRIPTION:通知\r\n\
+                   END:VALARM\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VTIMEZONE\r\n\
+                   TZID:Asia/Tokyo\r\n\
+                   BEGIN:STANDARD\r\n\
+                   DTSTART:19700101T000000\r\n\
+                   TZOFFSETFROM:+0900\r\n\
+                   TZOFFSETTO:+0900\r\n\
+                   END:STANDARD\r\n\
+                   END:VTIMEZONE\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;TZID=Asia/Tokyo:20251105T140000\r\n\
+                   DURATION:PT1H15M\r\n\
+                   SUMMARY:定例\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;VALUE=DATE:20251106\r\n\
+                   SUMMARY:終日の予定\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART:20251107T000000Z\r\n\
+                   DTEND:20251107T010000Z\r\n\
+                   SUMMARY:中止\r\n\
+                   STATUS:CANCELLED\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+    let week = DateRange::new(date(2025, 11, 3), date(2025, 11, 9));
+    let parsed = ical::parse_calendar(content, &week).expect("読み込みに失敗");
+    // 終日の予定と中止された予定は読み込まない
+    let events = parsed.events;
+    assert_eq!(events.len(), 2);
+    assert!(parsed.skipped.is_empty());
+
+    let utc = |h, m| Utc.with_ymd_and_hms(2025, 11, 5, h, m, 0).unwrap().with_timezone(&Local);
+    assert_eq!(events[0].uid.as_deref(), Some("a@example.com"));
+    assert_eq!(events[0].summary, "設計レビュー");
+    assert_eq!(events[0].description.as_deref(), Some("A社, B社\n資料あり"));
+    assert_eq!(events[0].categories, vec!["review", "team"]);
+    assert_eq!((events[0].start, events[0].end), (utc(0, 0), utc(1, 30)));
+
+    // TZID 付きの時刻は VTIMEZONE の定義で変換する
+    assert_eq!(events[1].summary, "定例");
+    assert_eq!((events[1].start, events[1].end), (utc(5, 0), utc(6, 15)));
+
+    // 開始日が期間外の予定は読み込まない
+    let next_week = DateRange::new(date(2025, 11, 10), date(2025, 11, 16));
+    assert!(ical::parse_calendar(content, &next_week).unwrap().events.is_empty());
+
+    let entry = events[0].to_entry(&["cal".to_string()]);
+    assert_eq!(entry.task, "設計レビュー");
+    assert_eq!(entry.end, Some(utc(1, 30)));
+    assert_eq!(entry.tags, vec!["review", "team", "cal"]);
+
+    assert!(matches!(ical::parse_calendar("SUMMARY:会議", &week), Err(TimeCheckerError::InvalidCalendar(_))));
+
+    // 範囲外の期間はパニックせずにエラー
+    for duration in ["P99999999999999W", "P9999999999D", "PT9223372036854775807S"] {
+        let content = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART:20251105T000000Z\r\nDURATION:{}\r\nSUMMARY:会議\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            duration
+        );
+        assert!(matches!(ical::parse_calendar(&content, &week), Err(TimeCheckerError::InvalidCalendar(_))));
+    }
+}
+
+/// 夏時間のあるタイムゾーン（米国東部）の定義
+const NEW_YORK: &str = "BEGIN:VTIMEZONE\r\n\
+                        TZID:America/New_York\r\n\
+                        BEGIN:DAYLIGHT\r\n\
+                        DTSTART:20070311T020000\r\n\
+                        RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r\n\
+                        TZOFFSETFROM:-0500\r\n\
+                        TZOFFSETTO:-0400\r\n\
+                        END:DAYLIGHT\r\n\
+                        BEGIN:STANDARD\r\n\
+                        DTSTART:20071104T020000\r\n\
+                        RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r\n\
+                        TZOFFSETFROM:-0400\r\n\
+                        TZOFFSETTO:-0500\r\n\
+                        END:STANDARD\r\n\
+                        END:VTIMEZONE\r\n";
+
+#[test]
+fn test_parse_calendar_expands_recurring_events() {
+    // 毎週月・水曜 9:00（米国東部）、11/5 は除外、11/12 は 10:00 に変更
+    let content = format!(
+        "BEGIN:VCALENDAR\r\n{}\
+         BEGIN:VEVENT\r\n\
+         UID:standup@example.com\r\n\
+         DTSTART;TZID=America/New_York:20251020T090000\r\n\
+         DTEND;TZID=America/New_York:20251020T091500\r\n\
+         RRULE:FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20251231T235959Z\r\n\
+         EXDATE;TZID=America/New_York:20251105T090000\r\n\
+         SUMMARY:朝会\r\n\
+         END:VEVENT\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:standup@example.com\r\n\
+         RECURRENCE-ID;TZID=America/New_York:20251112T090000\r\n\
+         DTSTART;TZID=America/New_York:20251112T100000\r\n\
+         DTEND;TZID=America/New_York:20251112T101500\r\n\
+         SUMMARY:朝会\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        NEW_YORK
+    );
+
+    let range = DateRange::new(date(2025, 10, 27), date(2025, 11, 13));
+    let parsed = ical::parse_calendar(&content, &range).expect("読み込みに失敗");
+    assert!(parsed.skipped.is_empty());
+
+    let utc = |d, h| Utc.with_ymd_and_hms(2025, if d > 20 { 10 } else { 11 }, d, h, 0, 0).unwrap();
+    let starts: Vec<_> = parsed.events.iter().map(|e| e.start.with_timezone(&Utc)).collect();
+    // 11/2 に夏時間が終わるので、UTC では1時間ずれる
+    assert_eq!(starts, vec![utc(27, 13), utc(29, 13), utc(3, 14), utc(10, 14), utc(12, 15)]);
+    assert!(parsed.events.iter().all(|e| e.end - e.start == chrono::Duration::minutes(15)));
+}
+
+#[test]
+fn test_parse_calendar_skips_unresolvable_events() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART;TZID=Europe/Berlin:20251105T100000\r\n\
+                   DTEND;TZID=Europe/Berlin:20251105T110000\r\n\
+                   SUMMARY:定義のないタイムゾーン\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   DTSTART:20251001T000000Z\r\n\
+                   DTEND:20251001T010000Z\r\n\
+                   RRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1\r\n\
+                   SUMMARY:月末の締め\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+    let week = DateRange::new(date(2025, 11, 3), date(2025, 11, 9));
+    let parsed = ical::parse_calendar(content, &week).expect("読み込みに失敗");
+    assert!(parsed.events.is_empty());
+
+    let reasons: Vec<_> = parsed.skipped.iter().map(|s| (s.event.summary.as_str(), &s.reason)).collect();
+    assert_eq!(
+        reasons,
+        vec![
+            (
+                "月末の締め",
+                &SkipReason::UnsupportedRecurrence("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1".to_string())
+            ),
+            ("定義のないタイムゾーン", &SkipReason::UnknownTimeZone("Europe/Berlin".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn test_import_skips_overlapping_and_unfinished() {
+    let at = |h, m| Local.with_ymd_and_hms(2025, 11, 5, h, m, 0).unwrap();
+    let tracker = Tracker::with_clock(MemoryStorage::new(), Arc::new(FixedClock::new(at(12, 0))));
+    tracker.add_entry("実装".to_string(), None, at(9, 0), at(10, 0)).unwrap();
+
+    let candidate = |task: &str, start, end| TimeEntry {
+        task: task.to_string(),
+        start,
+        end: Some(end),
+        ..Default::default()
+    };
+    let candidates = vec![
+        candidate("午後の会議", at(13, 0), at(14, 0)),
+        candidate("朝会", at(9, 30), at(9, 45)),
+        candidate("定例", at(10, 0), at(11, 0)),
+    ];
+
+    // --dry-run では保存しない
+    let preview = tracker.preview_import(candidates.clone()).expect("確認に失敗");
+    assert_eq!(preview.added.len(), 1);
+    assert_eq!(tracker.store().load().unwrap().len(), 1);
+
+    let result = tracker.import_entries(candidates).expect("インポートに失敗");
+    assert_eq!(result.added.len(), 1);
+    assert_eq!((result.added[0].id, result.added[0].task.as_str()), (2, "定例"));
+
+    let reasons: Vec<_> = result.skipped.iter().map(|s| (s.entry.task.as_str(), &s.reason)).collect();
+    assert_eq!(
+        reasons,
+        vec![("朝会", &SkipReason::Overlapping(1)), ("午後の会議", &SkipReason::NotFinished)]
+    );
+
+    // 同じ予定を取り込み直しても重複しない
+    let again = tracker.import_entries(vec![candidate("定例", at(10, 0), at(11, 0))]).unwrap();
+    assert!(again.added.is_empty());
+    assert_eq!(again.skipped[0].reason, SkipReason::Overlapping(2));
+}
@@ -1,6 +1,7 @@
 use chrono::{Local, TimeZone};
 use tempfile::tempdir;
 use time_checker::data::{DataStore, TimeEntry};
+use time_checker::storage::Storage;
 use time_checker::tracker::Tracker;
 
 /// エンドツーエンドのワークフローテスト
@@ -212,12 +213,14 @@ fn test_today_filtering() {
             start: Local.from_local_datetime(&yesterday).unwrap(),
             end: Some(Local.from_local_datetime(&yesterday.checked_add_signed(chrono::Duration::hours(1)).unwrap()).unwrap()),
             note: None,
+            ..Default::default()
         },
         TimeEntry {
             task: "今日のタスク".to_string(),
             start: Local.from_local_datetime(&today_start).unwrap(),
             end: Some(Local.from_local_datetime(&today_end).unwrap()),
             note: None,
+            ..Default::default()
         },
     ];
 
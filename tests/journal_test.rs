@@ -0,0 +1,197 @@
+use chrono::{Duration, Local, TimeZone};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Arc;
+use tempfile::tempdir;
+use time_checker::clock::FixedClock;
+use time_checker::data::TimeEntry;
+use time_checker::journal::{diff_events, JournalEvent, JournalStore};
+use time_checker::storage::Storage;
+use time_checker::tracker::{EntryEdit, Tracker};
+
+fn journal_tracker(dir: &std::path::Path) -> (Tracker<JournalStore>, Arc<FixedClock>) {
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let store = JournalStore::with_clock(dir.to_path_buf(), clock.clone());
+    (Tracker::with_clock(store, clock.clone()), clock)
+}
+
+#[test]
+fn test_diff_events() {
+    let start = Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap();
+    let running = TimeEntry { id: 1, task: "実装".to_string(), start, ..Default::default() };
+    let other = TimeEntry { id: 2, task: "会議".to_string(), start, end: Some(start), ..Default::default() };
+    let before = vec![running.clone(), other.clone()];
+
+    // 終了時刻が付いただけなら停止、それ以外の変更は編集
+    let stopped = TimeEntry { end: Some(start + Duration::hours(1)), ..running.clone() };
+    let renamed = TimeEntry { task: "設計".to_string(), ..running.clone() };
+    let new_entry = TimeEntry { id: 3, task: "レビュー".to_string(), start, ..Default::default() };
+
+    assert_eq!(
+        diff_events(&before, &[stopped.clone(), new_entry.clone()]),
+        vec![
+            JournalEvent::Delete { id: 2 },
+            JournalEvent::Stop { id: 1, end: start + Duration::hours(1) },
+            JournalEvent::Start { entry: new_entry },
+        ]
+    );
+    assert_eq!(
+        diff_events(&before, &[renamed.clone(), other.clone()]),
+        vec![JournalEvent::Edit { entry: renamed }]
+    );
+    assert!(diff_events(&before, &before).is_empty());
+}
+
+#[test]
+fn test_journal_records_every_change_and_replays() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let (tracker, clock) = journal_tracker(dir.path());
+
+    tracker.start_task("実装".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(60));
+    tracker.start_task("会議".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(30));
+    tracker.stop_task().expect("タスクの停止に失敗");
+    tracker
+        .edit_entry(2, EntryEdit { task: Some("定例".to_string()), ..Default::default() })
+        .expect("編集に失敗");
+    tracker.delete_entry(1).expect("削除に失敗");
+
+    // start, stop + start, stop, edit, delete の順に記録されている
+    let history = tracker.store().history().expect("履歴の取得に失敗");
+    let kinds: Vec<&str> = history
+        .iter()
+        .map(|r| match r.event {
+            JournalEvent::Start { .. } => "start",
+            JournalEvent::Stop { .. } => "stop",
+            JournalEvent::Add { .. } => "add",
+            JournalEvent::Edit { .. } => "edit",
+            JournalEvent::Delete { .. } => "delete",
+        })
+        .collect();
+    assert_eq!(kinds, vec!["start", "stop", "start", "stop", "edit", "delete"]);
+    assert_eq!(history[5].at, Local.with_ymd_and_hms(2025, 11, 5, 10, 30, 0).unwrap());
+
+    // 別のインスタンスから読み込んでも同じ状態が復元される
+    let entries = JournalStore::new(dir.path().to_path_buf()).load().expect("読み込みに失敗");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, 2);
+    assert_eq!(entries[0].task, "定例");
+    assert_eq!(entries[0].end, Some(Local.with_ymd_and_hms(2025, 11, 5, 10, 30, 0).unwrap()));
+}
+
+#[test]
+fn test_journal_compaction_keeps_state_and_history() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let store = JournalStore::with_clock(dir.path().to_path_buf(), clock.clone()).with_compact_threshold(4);
+    let tracker = Tracker::with_clock(store, clock.clone());
+
+    for i in 0..5 {
+        tracker.start_task(format!("タスク{}", i), None).expect("タスクの開始に失敗");
+        clock.advance(Duration::minutes(10));
+    }
+
+    // しきい値を超えたのでスナップショットにまとめられている
+    let store = tracker.store();
+    assert!(store.snapshot_file().exists());
+    assert!(store.history_file().exists());
+    let journal = std::fs::read_to_string(store.journal_file()).unwrap();
+    assert!(journal.lines().count() <= 4);
+
+    let entries = store.load().expect("読み込みに失敗");
+    assert_eq!(entries.len(), 5);
+    assert_eq!(entries.iter().filter(|e| e.end.is_none()).count(), 1);
+
+    // 監査用の履歴はすべて残っている（start 5件と stop 4件）
+    assert_eq!(store.history().expect("履歴の取得に失敗").len(), 9);
+
+    store.compact().expect("コンパクションに失敗");
+    assert_eq!(store.load().expect("読み込みに失敗"), entries);
+}
+
+#[test]
+fn test_journal_does_not_reuse_deleted_ids() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let (tracker, clock) = journal_tracker(dir.path());
+
+    tracker.start_task("設計".to_string(), None).unwrap();
+    clock.advance(Duration::minutes(30));
+    tracker.start_task("実装".to_string(), None).unwrap();
+    tracker.delete_entry(2).unwrap();
+
+    // ジャーナルを読み直しても、削除したエントリのIDは使わない
+    let reopened = JournalStore::new(dir.path().to_path_buf());
+    assert_eq!(reopened.load_entries().unwrap().next_id(), 3);
+
+    // スナップショットにまとめた後も同じ
+    tracker.store().compact().expect("コンパクションに失敗");
+    clock.advance(Duration::minutes(30));
+    tracker.start_task("レビュー".to_string(), None).unwrap();
+    assert_eq!(tracker.store().get_current_task().unwrap().unwrap().id, 3);
+}
+
+#[test]
+fn test_journal_ignores_truncated_last_line() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let (tracker, clock) = journal_tracker(dir.path());
+
+    tracker.start_task("実装".to_string(), None).expect("タスクの開始に失敗");
+
+    // 追記の途中で強制終了した状態を再現
+    let mut file = OpenOptions::new().append(true).open(tracker.store().journal_file()).unwrap();
+    file.write_all(b"{\"at\":\"2025-11-05T09:30:00+09:00\",\"event\":\"st").unwrap();
+
+    assert_eq!(tracker.store().load().expect("読み込みに失敗").len(), 1);
+
+    // 次の追記で不完全な行は取り除かれる
+    clock.advance(Duration::minutes(30));
+    tracker.stop_task().expect("タスクの停止に失敗");
+    let journal = std::fs::read_to_string(tracker.store().journal_file()).unwrap();
+    assert_eq!(journal.lines().count(), 2);
+    assert!(tracker.store().load().unwrap()[0].end.is_some());
+}
+
+#[test]
+fn test_journal_ignores_torn_multibyte_last_line() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let (tracker, _) = journal_tracker(dir.path());
+
+    tracker.start_task("設計".to_string(), None).expect("タスクの開始に失敗");
+    tracker.store().compact().expect("コンパクションに失敗");
+    tracker.start_task("実装".to_string(), None).expect("タスクの開始に失敗");
+
+    // タスク名のマルチバイト文字の途中で追記が止まった状態を再現（ジャーナルと履歴の両方）
+    let line = "{\"at\":\"2025-11-05T09:30:00+09:00\",\"event\":\"start\",\"entry\":{\"task\":\"実".as_bytes();
+    let torn = &line[..line.len() - 1];
+    assert!(std::str::from_utf8(torn).is_err());
+    for path in [tracker.store().journal_file(), tracker.store().history_file()] {
+        OpenOptions::new().append(true).open(path).unwrap().write_all(torn).unwrap();
+    }
+
+    assert_eq!(tracker.store().load().expect("読み込みに失敗").len(), 2);
+    assert_eq!(tracker.store().history().expect("履歴の取得に失敗").len(), 3);
+
+    // 履歴の不完全な行もコンパクションのときに取り除かれる
+    tracker.store().compact().expect("コンパクションに失敗");
+    assert_eq!(tracker.store().history().expect("履歴の取得に失敗").len(), 3);
+}
+
+#[test]
+fn test_journal_compaction_resumes_without_duplicating_history() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let (tracker, clock) = journal_tracker(dir.path());
+
+    tracker.start_task("設計".to_string(), None).unwrap();
+    clock.advance(Duration::minutes(30));
+    tracker.start_task("実装".to_string(), None).unwrap();
+
+    // 履歴への追記の後、ジャーナルを空にする前に中断した状態を再現
+    let store = tracker.store();
+    let journal = std::fs::read(store.journal_file()).unwrap();
+    std::fs::write(store.history_file(), &journal).unwrap();
+
+    store.compact().expect("コンパクションに失敗");
+    assert_eq!(store.history().expect("履歴の取得に失敗").len(), 3);
+    assert_eq!(store.load().unwrap().len(), 2);
+}
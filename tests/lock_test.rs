@@ -0,0 +1,84 @@
+use chrono::{Duration, Local, TimeZone};
+use std::sync::Arc;
+use std::thread;
+use tempfile::tempdir;
+use time_checker::clock::FixedClock;
+use time_checker::data::DataStore;
+use time_checker::storage::Storage;
+use time_checker::error::TimeCheckerError;
+use time_checker::tracker::Tracker;
+
+#[test]
+fn test_concurrent_updates_are_not_lost() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("data.json");
+    let base = Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap();
+
+    // 別々のプロセスを想定し、スレッドごとに DataStore を作って同じファイルを更新する
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let data_file = data_file.clone();
+            thread::spawn(move || {
+                let clock = Arc::new(FixedClock::new(base + Duration::hours(12)));
+                let store = DataStore::with_clock(data_file, clock.clone());
+                let tracker = Tracker::with_clock(store, clock);
+
+                let start = base + Duration::minutes(10 * i);
+                tracker
+                    .add_entry(format!("タスク{}", i), None, start, start + Duration::minutes(10))
+                    .expect("エントリの追加に失敗");
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().expect("スレッドがパニックした");
+    }
+
+    let entries = DataStore::new(data_file).load().expect("読み込みに失敗");
+    assert_eq!(entries.len(), 8);
+
+    // IDも重複していない
+    let mut ids: Vec<u64> = entries.iter().map(|e| e.id).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 8);
+}
+
+#[test]
+fn test_lock_timeout_when_contended() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("data.json");
+
+    let holder = DataStore::new(data_file.clone());
+    let lock = holder.lock().expect("ロックの取得に失敗");
+    assert_eq!(lock.path(), dir.path().join("data.json.lock"));
+
+    // ロックが解放されなければタイムアウトする
+    let store = DataStore::new(data_file.clone()).with_lock_timeout(std::time::Duration::from_millis(100));
+    let tracker = Tracker::new(store);
+    let result = tracker.start_task("プログラミング".to_string(), None);
+    assert!(matches!(result, Err(TimeCheckerError::LockTimeout(_))));
+
+    // 読み込みはロックなしでできる
+    assert!(tracker.store().load().expect("読み込みに失敗").is_empty());
+
+    // 解放後は更新できる
+    drop(lock);
+    tracker.start_task("プログラミング".to_string(), None).expect("タスクの開始に失敗");
+    assert_eq!(tracker.store().load().expect("読み込みに失敗").len(), 1);
+}
+
+#[test]
+fn test_transaction_does_not_save_on_error() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let store = DataStore::new(dir.path().join("data.json"));
+
+    let result: Result<(), _> = store.transaction(|entries| {
+        entries.push(Default::default());
+        Err(TimeCheckerError::NoActiveTask)
+    });
+
+    assert!(result.is_err());
+    assert!(!store.data_file().exists());
+}
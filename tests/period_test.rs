@@ -0,0 +1,76 @@
+use chrono::NaiveDate;
+use time_checker::period::{DateRange, Period};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn test_period_parse_keywords() {
+    assert_eq!("today".parse::<Period>().unwrap(), Period::Today);
+    assert_eq!("yesterday".parse::<Period>().unwrap(), Period::Yesterday);
+    assert_eq!("week".parse::<Period>().unwrap(), Period::Week);
+    assert_eq!("last-week".parse::<Period>().unwrap(), Period::LastWeek);
+    assert_eq!("month".parse::<Period>().unwrap(), Period::Month);
+    assert_eq!("last-month".parse::<Period>().unwrap(), Period::LastMonth);
+}
+
+#[test]
+fn test_period_parse_date_and_range() {
+    assert_eq!(
+        "2025-11-05".parse::<Period>().unwrap(),
+        Period::Date(date(2025, 11, 5))
+    );
+    assert_eq!(
+        "2025-11-01..2025-11-30".parse::<Period>().unwrap(),
+        Period::Range(date(2025, 11, 1), date(2025, 11, 30))
+    );
+}
+
+#[test]
+fn test_period_parse_invalid() {
+    assert!("fortnight".parse::<Period>().is_err());
+    assert!("2025-13-01".parse::<Period>().is_err());
+    assert!("2025-11-01..".parse::<Period>().is_err());
+
+    // 終了日が開始日より前の範囲は無効
+    assert!("2025-11-30..2025-11-01".parse::<Period>().is_err());
+}
+
+#[test]
+fn test_period_resolve_day_periods() {
+    // 2025-11-05 は水曜日
+    let today = date(2025, 11, 5);
+
+    assert_eq!(Period::Today.resolve(today), DateRange::single(today));
+    assert_eq!(Period::Yesterday.resolve(today), DateRange::single(date(2025, 11, 4)));
+}
+
+#[test]
+fn test_period_resolve_weeks() {
+    let today = date(2025, 11, 5);
+
+    // 週は月曜始まり
+    assert_eq!(
+        Period::Week.resolve(today),
+        DateRange::new(date(2025, 11, 3), date(2025, 11, 9))
+    );
+    assert_eq!(
+        Period::LastWeek.resolve(today),
+        DateRange::new(date(2025, 10, 27), date(2025, 11, 2))
+    );
+}
+
+#[test]
+fn test_period_resolve_months() {
+    assert_eq!(
+        Period::Month.resolve(date(2024, 2, 10)),
+        DateRange::new(date(2024, 2, 1), date(2024, 2, 29))
+    );
+
+    // 1月の「先月」は前年の12月
+    assert_eq!(
+        Period::LastMonth.resolve(date(2026, 1, 15)),
+        DateRange::new(date(2025, 12, 1), date(2025, 12, 31))
+    );
+}
@@ -0,0 +1,70 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use time_checker::recurrence::Recurrence;
+
+fn at(y: i32, m: u32, d: u32, h: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, 0, 0).unwrap()
+}
+
+fn occurrences(rule: &str, start: NaiveDateTime, to: NaiveDateTime) -> Vec<NaiveDateTime> {
+    let rule: Recurrence = rule.parse().expect("パースに失敗");
+    rule.occurrences(start, to, Some)
+}
+
+#[test]
+fn test_weekly_with_interval_and_count() {
+    // 2025-11-03 は月曜日
+    let result = occurrences("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=5", at(2025, 11, 3, 9), at(2026, 1, 1, 0));
+    assert_eq!(
+        result,
+        vec![at(2025, 11, 3, 9), at(2025, 11, 6, 9), at(2025, 11, 17, 9), at(2025, 11, 20, 9), at(2025, 12, 1, 9)]
+    );
+}
+
+#[test]
+fn test_until_and_range_end() {
+    let result = occurrences("FREQ=DAILY;UNTIL=20251105T090000Z", at(2025, 11, 3, 9), at(2026, 1, 1, 0));
+    assert_eq!(result, vec![at(2025, 11, 3, 9), at(2025, 11, 4, 9), at(2025, 11, 5, 9)]);
+
+    let result = occurrences("FREQ=DAILY;UNTIL=20251104", at(2025, 11, 3, 9), at(2026, 1, 1, 0));
+    assert_eq!(result, vec![at(2025, 11, 3, 9), at(2025, 11, 4, 9)]);
+
+    // 終わりのない規則も `to` までで止まる
+    assert_eq!(occurrences("FREQ=DAILY", at(2025, 11, 3, 9), at(2025, 11, 5, 0)).len(), 2);
+}
+
+#[test]
+fn test_monthly_by_day_and_month_day() {
+    // 毎月最終金曜日
+    let result = occurrences("FREQ=MONTHLY;BYDAY=-1FR", at(2025, 10, 31, 17), at(2026, 1, 1, 0));
+    assert_eq!(result, vec![at(2025, 10, 31, 17), at(2025, 11, 28, 17), at(2025, 12, 26, 17)]);
+
+    // 31日のない月は飛ばす
+    let result = occurrences("FREQ=MONTHLY;COUNT=3", at(2025, 10, 31, 17), at(2027, 1, 1, 0));
+    assert_eq!(result, vec![at(2025, 10, 31, 17), at(2025, 12, 31, 17), at(2026, 1, 31, 17)]);
+
+    // 月末
+    let result = occurrences("FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=2", at(2026, 1, 31, 9), at(2027, 1, 1, 0));
+    assert_eq!(result, vec![at(2026, 1, 31, 9), at(2026, 2, 28, 9)]);
+}
+
+#[test]
+fn test_yearly_by_month_and_day() {
+    // 3月の第2日曜日
+    let result = occurrences("FREQ=YEARLY;BYMONTH=3;BYDAY=2SU", at(2007, 3, 11, 2), at(2027, 1, 1, 0));
+    assert_eq!(result.len(), 20);
+    assert_eq!(result[18..], [at(2025, 3, 9, 2), at(2026, 3, 8, 2)]);
+}
+
+#[test]
+fn test_unsupported_rules() {
+    for rule in [
+        "FREQ=HOURLY",
+        "FREQ=MONTHLY;BYDAY=MO;BYSETPOS=1",
+        "FREQ=WEEKLY;BYDAY=1MO",
+        "FREQ=YEARLY;BYDAY=MO",
+        "BYDAY=MO",
+        "FREQ=DAILY;INTERVAL=0",
+    ] {
+        assert!(rule.parse::<Recurrence>().is_err(), "{}", rule);
+    }
+}
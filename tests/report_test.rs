@@ -0,0 +1,118 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use std::collections::HashMap;
+use std::time::Duration;
+use time_checker::config::ReportConfig;
+use time_checker::data::TimeEntry;
+use time_checker::period::{DateRange, Period};
+use time_checker::report::{HourFormat, MarkdownRenderer, MarkdownStyle, Report};
+
+fn minutes(m: u64) -> Duration {
+    Duration::from_secs(m * 60)
+}
+
+fn sample_report() -> Report {
+    let at = |h, m| Local.with_ymd_and_hms(2025, 11, 5, h, m, 0).unwrap();
+    let entry = |task: &str, start, end, note: Option<&str>| TimeEntry {
+        task: task.to_string(),
+        start,
+        end: Some(end),
+        note: note.map(str::to_string),
+        ..Default::default()
+    };
+    let entries = vec![
+        entry("会議", at(9, 0), at(10, 0), Some("週次|定例")),
+        entry("実装", at(10, 0), at(12, 15), None),
+        entry("会議", at(13, 0), at(13, 30), Some("ふりかえり")),
+        entry("会議", at(16, 0), at(16, 30), Some("週次|定例")),
+        entry("昼休憩", at(12, 15), at(13, 0), Some("集計外")),
+    ];
+    let totals = HashMap::from([("会議".to_string(), minutes(120)), ("実装".to_string(), minutes(135))]);
+
+    let range = DateRange::single(NaiveDate::from_ymd_opt(2025, 11, 5).unwrap());
+    Report::new(Period::Today, range, &totals, &entries)
+}
+
+#[test]
+fn test_hour_format() {
+    assert_eq!(HourFormat::Japanese.format(minutes(90)), "1時間30分");
+    assert_eq!(HourFormat::Decimal.format(minutes(135)), "2.25h");
+    assert_eq!(HourFormat::Clock.format(minutes(65) + Duration::from_secs(59)), "1:05");
+}
+
+#[test]
+fn test_report_collects_notes() {
+    let report = sample_report();
+
+    assert_eq!(report.rows.len(), 2);
+    assert_eq!(report.rows[0].task, "会議");
+    // 重複を除いて開始時刻の順、集計にないタスクのメモは使わない
+    assert_eq!(report.rows[0].notes, vec!["週次|定例", "ふりかえり"]);
+    assert!(report.rows[1].notes.is_empty());
+    assert_eq!(report.total(), minutes(255));
+}
+
+#[test]
+fn test_markdown_table() {
+    let report = sample_report();
+
+    assert_eq!(
+        MarkdownRenderer::default().render(&report),
+        "## 今日の作業時間 (2025-11-05)\n\n\
+         | タスク | 時間 |\n\
+         | --- | ---: |\n\
+         | 会議 | 2時間0分 |\n\
+         | 実装 | 2時間15分 |\n\
+         | **合計** | **4時間15分** |\n"
+    );
+
+    // メモの列では | をエスケープする
+    let rendered = MarkdownRenderer::default().with_notes(true).render(&report);
+    assert!(rendered.contains("| 会議 | 2時間0分 | 週次\\|定例 / ふりかえり |\n"));
+}
+
+#[test]
+fn test_markdown_list_with_config() {
+    let config = ReportConfig {
+        heading: "日報 {range}".to_string(),
+        heading_level: 3,
+        hours: HourFormat::Clock,
+        notes: true,
+        ..Default::default()
+    };
+    let renderer = MarkdownRenderer::new(&config).with_style(MarkdownStyle::List);
+
+    assert_eq!(
+        renderer.render(&sample_report()),
+        "### 日報 2025-11-05\n\n\
+         - 会議: 2:00\n\
+         \x20 - 週次|定例\n\
+         \x20 - ふりかえり\n\
+         - 実装: 2:15\n\
+         \n\
+         **合計: 4:15**\n"
+    );
+}
+
+#[test]
+fn test_markdown_mentions_excluded_tasks() {
+    let excluded = HashMap::from([("昼休憩".to_string(), minutes(45)), ("私用".to_string(), minutes(10))]);
+    let report = sample_report().with_excluded(&excluded);
+
+    // 合計は集計対象のタスクのみで、除外したタスクは別に書き添える
+    let rendered = MarkdownRenderer::default().render(&report);
+    assert!(rendered.ends_with(
+        "| **合計** | **4時間15分** |\n\n除外したタスク（合計に含まない）: 昼休憩 0時間45分、私用 0時間10分\n"
+    ));
+    let rendered = MarkdownRenderer::default().with_style(MarkdownStyle::List).render(&report);
+    assert!(rendered.ends_with("**合計: 4時間15分**\n\n除外したタスク（合計に含まない）: 昼休憩 0時間45分、私用 0時間10分\n"));
+
+    // 集計対象のタスクがなくても除外した時間はわかる
+    let range = DateRange::single(NaiveDate::from_ymd_opt(2025, 11, 5).unwrap());
+    let report = Report::new(Period::Today, range, &HashMap::new(), &[]).with_excluded(&excluded);
+    assert_eq!(
+        MarkdownRenderer::default().render(&report),
+        "## 今日の作業時間 (2025-11-05)\n\n\
+         集計対象の作業記録はありません\n\n\
+         除外したタスク（合計に含まない）: 昼休憩 0時間45分、私用 0時間10分\n"
+    );
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "sqlite")]
+
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use std::sync::Arc;
+use tempfile::tempdir;
+use time_checker::clock::FixedClock;
+use time_checker::data::{DataStore, TimeEntry};
+use time_checker::period::DateRange;
+use time_checker::sqlite::SqliteStore;
+use time_checker::storage::Storage;
+use time_checker::tracker::Tracker;
+
+fn sample_entries() -> Vec<TimeEntry> {
+    let start = Local.with_ymd_and_hms(2025, 11, 5, 23, 0, 0).unwrap();
+    vec![
+        TimeEntry {
+            id: 3,
+            task: "夜間対応".to_string(),
+            start,
+            end: Some(start + Duration::hours(2)),
+            note: Some("障害対応".to_string()),
+            paused: true,
+            resumed_from: None,
+            tags: Vec::new(),
+        },
+        TimeEntry {
+            id: 1,
+            task: "会議".to_string(),
+            start: start + Duration::hours(10),
+            end: Some(start + Duration::minutes(10 * 60 + 30) + Duration::nanoseconds(123_456_789)),
+            tags: vec!["定例".to_string(), "mtg".to_string()],
+            ..Default::default()
+        },
+        TimeEntry {
+            task: "実装作業".to_string(),
+            start: start + Duration::hours(11),
+            resumed_from: Some(3),
+            ..Default::default()
+        },
+    ]
+}
+
+#[test]
+fn test_sqlite_roundtrip_is_lossless() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let json = DataStore::new(dir.path().join("data.json"));
+    json.save(&sample_entries()).expect("保存に失敗");
+
+    // JSON → SQLite → JSON で内容も順序も変わらない
+    let original = json.load().expect("読み込みに失敗");
+    let database = SqliteStore::open(&dir.path().join("data.db")).expect("データベースを開けない");
+    database.save(&original).expect("保存に失敗");
+    assert_eq!(database.load().expect("読み込みに失敗"), original);
+
+    let exported = DataStore::new(dir.path().join("exported.json"));
+    exported.save(&database.load().unwrap()).expect("保存に失敗");
+    assert_eq!(exported.load().expect("読み込みに失敗"), original);
+}
+
+#[test]
+fn test_sqlite_indexed_queries() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let database = SqliteStore::open(&dir.path().join("data.db")).expect("データベースを開けない");
+    database.save(&sample_entries()).expect("保存に失敗");
+
+    // 日付をまたぐエントリは両方の日に含まれる
+    let day = |d| DateRange::single(NaiveDate::from_ymd_opt(2025, 11, d).unwrap());
+    assert_eq!(database.get_entries_in_range(day(5)).unwrap().len(), 1);
+    assert_eq!(database.get_entries_in_range(day(6)).unwrap().len(), 3);
+    assert!(database.get_entries_in_range(day(4)).unwrap().is_empty());
+
+    assert_eq!(database.get_current_task().unwrap().unwrap().task, "実装作業");
+    assert_eq!(database.get_entry(3).unwrap().unwrap().note, Some("障害対応".to_string()));
+    assert!(database.get_entry(99).unwrap().is_none());
+}
+
+#[test]
+fn test_tracker_with_sqlite_store() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let path = dir.path().join("data.db");
+
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let tracker = Tracker::with_clock(SqliteStore::open(&path).unwrap(), clock.clone());
+
+    tracker.start_task("プログラミング".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(60));
+    tracker.start_task("会議".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(30));
+    tracker.stop_task().expect("タスクの停止に失敗");
+    tracker.delete_entry(1).expect("削除に失敗");
+
+    // 開き直しても変更が残っている
+    let reopened = SqliteStore::open(&path).unwrap();
+    let entries = reopened.load().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, 2);
+    assert_eq!(entries[0].end, Some(Local.with_ymd_and_hms(2025, 11, 5, 10, 30, 0).unwrap()));
+
+    let summary = tracker.get_today_summary().expect("サマリーの取得に失敗");
+    assert_eq!(summary.get("会議").unwrap().as_secs(), 1800);
+    assert!(!summary.contains_key("プログラミング"));
+
+    // 削除したエントリのIDは開き直しても使わない
+    tracker.delete_entry(2).expect("削除に失敗");
+    let tracker = Tracker::with_clock(SqliteStore::open(&path).unwrap(), clock.clone());
+    tracker.start_task("レビュー".to_string(), None).expect("タスクの開始に失敗");
+    assert_eq!(tracker.store().get_current_task().unwrap().unwrap().id, 3);
+}
+
+#[test]
+fn test_sqlite_adds_pause_columns_to_old_database() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let path = dir.path().join("data.db");
+
+    // 一時停止のカラムがない古いデータベース
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE entries (
+            id INTEGER PRIMARY KEY, seq INTEGER NOT NULL, task TEXT NOT NULL, start_at TEXT NOT NULL,
+            end_at TEXT, note TEXT, start_ns INTEGER NOT NULL, end_ns INTEGER
+        );
+        INSERT INTO entries VALUES (1, 0, '会議', '2025-11-05T10:00:00+09:00', NULL, NULL, 1762304400000000000, NULL);",
+    )
+    .unwrap();
+    drop(conn);
+
+    let database = SqliteStore::open(&path).expect("データベースを開けない");
+    let entries = database.load().expect("読み込みに失敗");
+    assert_eq!(entries.len(), 1);
+    assert!(!entries[0].paused);
+    assert_eq!(entries[0].resumed_from, None);
+    assert!(entries[0].tags.is_empty());
+
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 12, 0, 0).unwrap()));
+    let tracker = Tracker::with_clock(database, clock.clone());
+    tracker.pause_task().expect("一時停止に失敗");
+    clock.advance(Duration::minutes(10));
+    let resumed = tracker.resume_task().expect("再開に失敗");
+    assert_eq!(resumed.resumed_from, Some(1));
+}
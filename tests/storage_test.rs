@@ -0,0 +1,86 @@
+use chrono::{Duration, Local, TimeZone};
+use std::sync::Arc;
+use tempfile::tempdir;
+use time_checker::clock::FixedClock;
+use time_checker::data::{DataStore, TimeEntry};
+use time_checker::error::TimeCheckerError;
+use time_checker::period::DateRange;
+use time_checker::storage::{MemoryStorage, Storage};
+use time_checker::tracker::Tracker;
+
+#[test]
+fn test_tracker_with_memory_storage() {
+    let clock = Arc::new(FixedClock::new(Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()));
+    let tracker = Tracker::with_clock(MemoryStorage::new(), clock.clone());
+
+    tracker.start_task("プログラミング".to_string(), None).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(60));
+    tracker.start_task("会議".to_string(), Some("週次定例".to_string())).expect("タスクの開始に失敗");
+    clock.advance(Duration::minutes(30));
+
+    let current = tracker.store().get_current_task().expect("取得に失敗").unwrap();
+    assert_eq!(current.id, 2);
+    assert_eq!(current.task, "会議");
+
+    tracker.stop_task().expect("タスクの停止に失敗");
+    let summary = tracker.get_today_summary().expect("サマリーの取得に失敗");
+    assert_eq!(summary.get("プログラミング").unwrap().as_secs(), 3600);
+    assert_eq!(summary.get("会議").unwrap().as_secs(), 1800);
+
+    // 重なりの検証も同じように働く
+    let result = tracker.add_entry(
+        "レビュー".to_string(),
+        None,
+        Local.with_ymd_and_hms(2025, 11, 5, 9, 30, 0).unwrap(),
+        Local.with_ymd_and_hms(2025, 11, 5, 9, 45, 0).unwrap(),
+    );
+    assert!(matches!(result, Err(TimeCheckerError::OverlappingEntry(1))));
+    assert_eq!(tracker.store().load().unwrap().len(), 2);
+}
+
+#[test]
+fn test_memory_storage_append_and_range_query() {
+    let start = Local.with_ymd_and_hms(2025, 11, 5, 23, 0, 0).unwrap();
+    let storage = MemoryStorage::with_entries(vec![TimeEntry {
+        task: "夜間対応".to_string(),
+        start,
+        end: Some(start + Duration::hours(2)),
+        ..Default::default()
+    }]);
+
+    let appended = storage
+        .append(TimeEntry {
+            task: "翌日の作業".to_string(),
+            start: start + Duration::hours(10),
+            end: Some(start + Duration::hours(11)),
+            ..Default::default()
+        })
+        .expect("追加に失敗");
+    assert_eq!(appended.id, 2);
+
+    let next_day = DateRange::single(chrono::NaiveDate::from_ymd_opt(2025, 11, 6).unwrap());
+    let entries = storage.get_entries_in_range(next_day).expect("取得に失敗");
+    assert_eq!(entries.len(), 2);
+    assert_eq!(storage.get_entry(1).unwrap().unwrap().task, "夜間対応");
+}
+
+#[test]
+fn test_boxed_storage_can_be_chosen_at_runtime() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("data.json");
+
+    for use_file in [true, false] {
+        let storage: Box<dyn Storage> = if use_file {
+            Box::new(DataStore::new(data_file.clone()))
+        } else {
+            Box::new(MemoryStorage::new())
+        };
+
+        let tracker = Tracker::new(storage);
+        tracker.start_task("プログラミング".to_string(), None).expect("タスクの開始に失敗");
+        assert_eq!(tracker.store().load().unwrap().len(), 1);
+    }
+
+    // メモリ上の保存先はファイルに書き込まない
+    assert_eq!(DataStore::new(data_file).load().unwrap().len(), 1);
+}
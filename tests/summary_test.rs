@@ -0,0 +1,132 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use time_checker::filter::TaskFilter;
+use time_checker::period::DateRange;
+use time_checker::storage::MemoryStorage;
+use time_checker::summary::{task_path, EntryDetail, PeriodSummary, PeriodTagSummary, Summary, TaskTree};
+use time_checker::tracker::Tracker;
+
+fn minutes(m: u64) -> Duration {
+    Duration::from_secs(m * 60)
+}
+
+fn sample_totals() -> HashMap<String, Duration> {
+    HashMap::from([
+        ("clientA/設計/レビュー".to_string(), minutes(30)),
+        ("clientA/設計".to_string(), minutes(30)),
+        ("clientA/実装".to_string(), minutes(60)),
+        ("clientB/サポート".to_string(), minutes(20)),
+        ("昼休憩".to_string(), minutes(40)),
+    ])
+}
+
+#[test]
+fn test_task_path() {
+    assert_eq!(task_path("clientA/設計/レビュー"), vec!["clientA", "設計", "レビュー"]);
+    assert_eq!(task_path("/clientA//設計 / "), vec!["clientA", "設計"]);
+    assert_eq!(task_path("昼休憩"), vec!["昼休憩"]);
+}
+
+#[test]
+fn test_task_tree_subtotals() {
+    let tree = TaskTree::new(&sample_totals());
+    assert_eq!(tree.total(), minutes(180));
+
+    let names: Vec<_> = tree.roots.iter().map(|n| n.name.as_str()).collect();
+    assert_eq!(names, vec!["clientA", "clientB", "昼休憩"]);
+
+    let client_a = &tree.roots[0];
+    assert_eq!(client_a.total, minutes(120));
+    assert!(client_a.own.is_zero());
+
+    // 「設計」は子の「レビュー」と自身の記録の両方を持つ
+    let design = client_a.children.iter().find(|n| n.name == "設計").unwrap();
+    assert_eq!(design.total, minutes(60));
+    assert_eq!(design.own, minutes(30));
+    assert_eq!(design.children[0].name, "レビュー");
+    assert_eq!(design.children[0].total, minutes(30));
+}
+
+#[test]
+fn test_task_tree_collapse() {
+    let tree = TaskTree::new(&sample_totals()).collapse(1);
+    assert!(tree.roots.iter().all(|n| n.children.is_empty()));
+    assert_eq!(tree.roots[0].own, minutes(120));
+    assert_eq!(tree.total(), minutes(180));
+
+    let tree = TaskTree::new(&sample_totals()).collapse(2);
+    let design = tree.roots[0].children.iter().find(|n| n.name == "設計").unwrap();
+    assert!(design.children.is_empty());
+    assert_eq!(design.own, minutes(60));
+}
+
+#[test]
+fn test_tracker_task_tree_excludes_filtered_tasks() {
+    let tracker = Tracker::new(MemoryStorage::new());
+    let at = |h, m| Local.with_ymd_and_hms(2025, 11, 5, h, m, 0).unwrap();
+    tracker.add_entry("clientA/設計".to_string(), None, at(9, 0), at(10, 0)).unwrap();
+    tracker.add_entry("clientA/実装".to_string(), None, at(10, 0), at(10, 30)).unwrap();
+    tracker.add_entry("昼休憩".to_string(), None, at(12, 0), at(13, 0)).unwrap();
+
+    let range = DateRange::single(NaiveDate::from_ymd_opt(2025, 11, 5).unwrap());
+    let exclude = TaskFilter::parse(&["昼休憩"]).unwrap();
+    let tree = tracker.get_task_tree(range, &exclude).expect("集計に失敗");
+
+    assert_eq!(tree.roots.len(), 1);
+    assert_eq!(tree.roots[0].name, "clientA");
+    assert_eq!(tree.roots[0].total, minutes(90));
+    assert_eq!(tree.roots[0].children.len(), 2);
+}
+
+#[test]
+fn test_period_summary_json() {
+    let date = |d| NaiveDate::from_ymd_opt(2025, 11, d).unwrap();
+    let range = DateRange::new(date(3), date(9));
+    let exclude = TaskFilter::parse(&["昼休憩"]).unwrap();
+    let summary = Summary::new(sample_totals(), &exclude);
+    let output = PeriodSummary::new(range, &[], &summary).with_tree(TaskTree::new(&summary.included).collapse(1));
+
+    let value = serde_json::to_value(&output).expect("シリアライズに失敗");
+    assert_eq!(value["start"], "2025-11-03");
+    assert_eq!(value["end"], "2025-11-09");
+    assert_eq!(value["tasks"][0], json!({ "task": "clientA/実装", "seconds": 3600 }));
+    assert_eq!(value["excluded"], json!([{ "task": "昼休憩", "seconds": 2400 }]));
+    assert_eq!(value["subtotal_seconds"], 140 * 60);
+    assert_eq!(value["total_seconds"], 180 * 60);
+    assert_eq!(value["tree"][0], json!({ "name": "clientA", "total_seconds": 7200, "own_seconds": 7200, "children": [] }));
+    // タグで絞り込んでいなければ tags は出力しない
+    assert!(value.get("tags").is_none());
+}
+
+#[test]
+fn test_tag_summary_and_entry_json() {
+    let tracker = Tracker::new(MemoryStorage::new());
+    let at = |h, m| Local.with_ymd_and_hms(2025, 11, 5, h, m, 0).unwrap();
+    let tags = vec!["backend".to_string()];
+    tracker.add_tagged_entry("実装".to_string(), None, tags.clone(), at(9, 0), at(10, 30)).unwrap();
+    tracker.add_entry("会議".to_string(), Some("定例".to_string()), at(11, 0), at(11, 20)).unwrap();
+
+    let range = DateRange::single(NaiveDate::from_ymd_opt(2025, 11, 5).unwrap());
+    let summary = tracker.get_tag_summary(range, &[], &TaskFilter::none()).unwrap();
+    let value = serde_json::to_value(PeriodTagSummary::new(range, &[], &summary)).unwrap();
+    assert_eq!(value["tags"], json!([{ "tag": "backend", "seconds": 5400 }]));
+    assert_eq!(value["untagged_seconds"], 1200);
+    assert_eq!(value["excluded"], json!([]));
+
+    // 除外されたタスクは別枠
+    let exclude = TaskFilter::parse(&["会議"]).unwrap();
+    let summary = tracker.get_tag_summary(range, &[], &exclude).unwrap();
+    let value = serde_json::to_value(PeriodTagSummary::new(range, &[], &summary)).unwrap();
+    assert_eq!(value["untagged_seconds"], 0);
+    assert_eq!(value["excluded"], json!([{ "task": "会議", "seconds": 1200 }]));
+
+    // 進行中のエントリは現在までの経過時間
+    let running = tracker.start_tagged_task_at("レビュー".to_string(), None, Vec::new(), at(12, 0)).unwrap();
+    let value = serde_json::to_value(EntryDetail::new(running, at(12, 45))).unwrap();
+    assert_eq!(value["task"], "レビュー");
+    assert_eq!(value["end"], serde_json::Value::Null);
+    assert_eq!(value["elapsed_seconds"], 2700);
+    assert!(value.get("worked_seconds").is_none());
+}
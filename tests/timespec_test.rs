@@ -0,0 +1,92 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use chrono::Duration;
+use time_checker::timespec::{parse_datetime, parse_duration, parse_past_datetime, parse_time_range, resolve_at};
+use time_checker::error::TimeCheckerError;
+
+fn base() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 11, 5).unwrap()
+}
+
+#[test]
+fn test_parse_time_only_uses_base_date() {
+    let parsed = parse_datetime("09:15", base()).expect("パースに失敗");
+    assert_eq!(parsed, Local.with_ymd_and_hms(2025, 11, 5, 9, 15, 0).unwrap());
+
+    let parsed = parse_datetime("23:59:30", base()).expect("パースに失敗");
+    assert_eq!(parsed, Local.with_ymd_and_hms(2025, 11, 5, 23, 59, 30).unwrap());
+}
+
+#[test]
+fn test_parse_date_and_time() {
+    let expected = Local.with_ymd_and_hms(2025, 11, 4, 18, 30, 0).unwrap();
+    assert_eq!(parse_datetime("2025-11-04 18:30", base()).unwrap(), expected);
+    assert_eq!(parse_datetime("2025-11-04T18:30", base()).unwrap(), expected);
+}
+
+#[test]
+fn test_parse_rfc3339() {
+    let parsed = parse_datetime("2025-11-05T00:00:00Z", base()).expect("パースに失敗");
+    assert_eq!(parsed.timestamp(), 1762300800);
+}
+
+#[test]
+fn test_parse_invalid_time() {
+    assert!(parse_datetime("25:00", base()).is_err());
+    assert!(parse_datetime("morning", base()).is_err());
+    assert!(parse_datetime("", base()).is_err());
+}
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("10m").unwrap(), Duration::minutes(10));
+    assert_eq!(parse_duration("1h30m").unwrap(), Duration::minutes(90));
+    assert_eq!(parse_duration("90s").unwrap(), Duration::seconds(90));
+    // 単位のない数値は分
+    assert_eq!(parse_duration("25").unwrap(), Duration::minutes(25));
+
+    assert!(parse_duration("").is_err());
+    assert!(parse_duration("1x").is_err());
+    assert!(parse_duration("1h30").is_err());
+    assert!(parse_duration("h").is_err());
+    // 範囲外の値はパニックせずにエラー
+    assert!(parse_duration("99999999999999999h").is_err());
+    assert!(parse_duration("9223372036854775807s9223372036854775807s").is_err());
+}
+
+#[test]
+fn test_parse_past_datetime_rolls_back_to_yesterday() {
+    let now = Local.with_ymd_and_hms(2025, 11, 5, 0, 30, 0).unwrap();
+
+    // 現在より前の時刻は今日
+    let parsed = parse_past_datetime("00:10", now).unwrap();
+    assert_eq!(parsed, Local.with_ymd_and_hms(2025, 11, 5, 0, 10, 0).unwrap());
+
+    // 現在より後の時刻は前日
+    let parsed = parse_past_datetime("23:50", now).unwrap();
+    assert_eq!(parsed, Local.with_ymd_and_hms(2025, 11, 4, 23, 50, 0).unwrap());
+}
+
+#[test]
+fn test_parse_time_range_crossing_midnight() {
+    let (start, end) = parse_time_range("14:00", "15:30", base()).unwrap();
+    assert_eq!(start, Local.with_ymd_and_hms(2025, 11, 5, 14, 0, 0).unwrap());
+    assert_eq!(end, Local.with_ymd_and_hms(2025, 11, 5, 15, 30, 0).unwrap());
+
+    // 終了時刻が開始時刻以前なら翌日
+    let (start, end) = parse_time_range("23:30", "01:00", base()).unwrap();
+    assert_eq!(start, Local.with_ymd_and_hms(2025, 11, 5, 23, 30, 0).unwrap());
+    assert_eq!(end, Local.with_ymd_and_hms(2025, 11, 6, 1, 0, 0).unwrap());
+}
+
+#[test]
+fn test_resolve_at() {
+    let now = Local.with_ymd_and_hms(2025, 11, 5, 10, 0, 0).unwrap();
+
+    assert_eq!(resolve_at(None, None, now).unwrap(), now);
+    assert_eq!(resolve_at(None, Some("15m"), now).unwrap(), now - Duration::minutes(15));
+    assert_eq!(
+        resolve_at(Some("09:00"), None, now).unwrap(),
+        Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap()
+    );
+    assert!(matches!(resolve_at(None, Some("999999999999m"), now), Err(TimeCheckerError::InvalidTime(_))));
+}
@@ -1,7 +1,10 @@
-use chrono::{Local, TimeZone};
+use chrono::{Local, NaiveDate, TimeZone};
 use tempfile::tempdir;
 use time_checker::data::DataStore;
-use time_checker::tracker::Tracker;
+use time_checker::filter::TaskFilter;
+use time_checker::period::DateRange;
+use time_checker::storage::{MemoryStorage, Storage};
+use time_checker::tracker::{EntryEdit, Tracker};
 
 #[test]
 fn test_tracker_start_new_task() {
@@ -57,8 +60,10 @@ fn test_tracker_stop_task() {
     // タスクを開始
     tracker.start_task("作業".to_string(), None).expect("タスクの開始に失敗");
 
-    // タスクを停止
-    tracker.stop_task().expect("タスクの停止に失敗");
+    // タスクを停止すると停止したエントリが返る
+    let stopped = tracker.stop_task().expect("タスクの停止に失敗");
+    assert_eq!(stopped.task, "作業");
+    assert!(stopped.end.is_some());
 
     // 現在のタスクがないことを確認
     let current = tracker.store().get_current_task().expect("取得に失敗");
@@ -98,12 +103,14 @@ fn test_tracker_get_today_summary() {
             start: Local.from_local_datetime(&today_start1).unwrap(),
             end: Some(Local.from_local_datetime(&today_end1).unwrap()),
             note: None,
+            ..Default::default()
         },
         TimeEntry {
             task: "会議".to_string(),
             start: Local.from_local_datetime(&today_start2).unwrap(),
             end: Some(Local.from_local_datetime(&today_end2).unwrap()),
             note: None,
+            ..Default::default()
         },
     ];
 
@@ -138,12 +145,14 @@ fn test_tracker_aggregate_same_task_names() {
             start: Local.from_local_datetime(&start1).unwrap(),
             end: Some(Local.from_local_datetime(&end1).unwrap()),
             note: None,
+            ..Default::default()
         },
         TimeEntry {
             task: "プログラミング".to_string(),
             start: Local.from_local_datetime(&start2).unwrap(),
             end: Some(Local.from_local_datetime(&end2).unwrap()),
             note: None,
+            ..Default::default()
         },
     ];
 
@@ -172,3 +181,350 @@ fn test_tracker_start_with_note() {
     assert_eq!(entries[0].task, "ドキュメント作成");
     assert_eq!(entries[0].note, Some("設計書更新".to_string()));
 }
+
+#[test]
+fn test_tracker_get_summary_for_range() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("tracker_range.json");
+    let store = DataStore::new(data_file);
+    let tracker = Tracker::new(store);
+
+    use time_checker::data::TimeEntry;
+    let entries = vec![
+        TimeEntry {
+            task: "設計".to_string(),
+            start: Local.with_ymd_and_hms(2025, 11, 3, 9, 0, 0).unwrap(),
+            end: Some(Local.with_ymd_and_hms(2025, 11, 3, 11, 0, 0).unwrap()),
+            note: None,
+            ..Default::default()
+        },
+        TimeEntry {
+            task: "設計".to_string(),
+            start: Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap(),
+            end: Some(Local.with_ymd_and_hms(2025, 11, 5, 10, 0, 0).unwrap()),
+            note: None,
+            ..Default::default()
+        },
+        TimeEntry {
+            task: "範囲外".to_string(),
+            start: Local.with_ymd_and_hms(2025, 11, 10, 9, 0, 0).unwrap(),
+            end: Some(Local.with_ymd_and_hms(2025, 11, 10, 10, 0, 0).unwrap()),
+            note: None,
+            ..Default::default()
+        },
+    ];
+
+    tracker.store().save(&entries).expect("保存に失敗");
+
+    // 2025-11-05 を含む週（11/3〜11/9）のサマリー
+    use time_checker::period::Period;
+    let today = chrono::NaiveDate::from_ymd_opt(2025, 11, 5).unwrap();
+    let summary = tracker
+        .get_summary(Period::Week.resolve(today))
+        .expect("サマリーの取得に失敗");
+
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary.get("設計").unwrap().as_secs(), 10800); // 3時間
+}
+
+#[test]
+fn test_tracker_summary_splits_entries_across_midnight() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("tracker_midnight.json");
+    let store = DataStore::new(data_file);
+    let tracker = Tracker::new(store);
+
+    // 23:00 から翌日 02:00 までの夜間作業
+    use time_checker::data::TimeEntry;
+    let entries = vec![TimeEntry {
+        task: "リリース作業".to_string(),
+        start: Local.with_ymd_and_hms(2025, 11, 4, 23, 0, 0).unwrap(),
+        end: Some(Local.with_ymd_and_hms(2025, 11, 5, 2, 0, 0).unwrap()),
+        note: None,
+        ..Default::default()
+    }];
+
+    tracker.store().save(&entries).expect("保存に失敗");
+
+    use time_checker::period::DateRange;
+    let day1 = chrono::NaiveDate::from_ymd_opt(2025, 11, 4).unwrap();
+    let day2 = chrono::NaiveDate::from_ymd_opt(2025, 11, 5).unwrap();
+
+    // 各日には日付の境界で切り分けた分だけが計上される
+    let summary1 = tracker.get_summary(DateRange::single(day1)).expect("サマリーの取得に失敗");
+    assert_eq!(summary1.get("リリース作業").unwrap().as_secs(), 3600); // 1時間
+
+    let summary2 = tracker.get_summary(DateRange::single(day2)).expect("サマリーの取得に失敗");
+    assert_eq!(summary2.get("リリース作業").unwrap().as_secs(), 7200); // 2時間
+
+    // 日ごとの集計でも同じように振り分けられる
+    let daily = tracker
+        .get_daily_summary(DateRange::new(day1, day2))
+        .expect("サマリーの取得に失敗");
+    assert_eq!(daily.len(), 2);
+    assert_eq!(daily[&day1].get("リリース作業").unwrap().as_secs(), 3600);
+    assert_eq!(daily[&day2].get("リリース作業").unwrap().as_secs(), 7200);
+}
+
+#[test]
+fn test_tracker_get_filtered_summary() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("tracker_filtered.json");
+    let store = DataStore::new(data_file);
+    let tracker = Tracker::new(store);
+
+    use time_checker::data::TimeEntry;
+    let entries = vec![
+        TimeEntry {
+            task: "実装作業".to_string(),
+            start: Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap(),
+            end: Some(Local.with_ymd_and_hms(2025, 11, 5, 12, 0, 0).unwrap()),
+            note: None,
+            ..Default::default()
+        },
+        TimeEntry {
+            task: "昼休憩".to_string(),
+            start: Local.with_ymd_and_hms(2025, 11, 5, 12, 0, 0).unwrap(),
+            end: Some(Local.with_ymd_and_hms(2025, 11, 5, 13, 0, 0).unwrap()),
+            note: None,
+            ..Default::default()
+        },
+    ];
+
+    tracker.store().save(&entries).expect("保存に失敗");
+
+    use time_checker::filter::TaskFilter;
+    use time_checker::period::DateRange;
+    let day = chrono::NaiveDate::from_ymd_opt(2025, 11, 5).unwrap();
+    let exclude = TaskFilter::parse(&["昼休憩"]).expect("パースに失敗");
+    let summary = tracker
+        .get_filtered_summary(DateRange::single(day), &exclude)
+        .expect("サマリーの取得に失敗");
+
+    // 除外されたタスクは別枠に入り、合計には含まれる
+    assert!(summary.included.contains_key("実装作業"));
+    assert!(summary.excluded.contains_key("昼休憩"));
+    assert_eq!(summary.subtotal().as_secs(), 3 * 3600);
+    assert_eq!(summary.total().as_secs(), 4 * 3600);
+}
+
+#[test]
+fn test_tracker_entries_have_sequential_ids() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let data_file = dir.path().join("tracker_ids.json");
+    let store = DataStore::new(data_file);
+    let tracker = Tracker::new(store);
+
+    tracker.start_task("タスク1".to_string(), None).expect("タスクの開始に失敗");
+    tracker.start_task("タスク2".to_string(), None).expect("タスクの開始に失敗");
+    tracker.stop_task().expect("タスクの停止に失敗");
+
+    let today = Local::now().date_naive();
+    let entries = tracker
+        .list_entries(time_checker::period::DateRange::single(today))
+        .expect("取得に失敗");
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].id, 1);
+    assert_eq!(entries[0].task, "タスク1");
+    assert_eq!(entries[1].id, 2);
+    assert_eq!(entries[1].task, "タスク2");
+}
+
+/// 9:00〜10:00 と 10:00〜11:00 の2件を持つTrackerを作成
+fn tracker_with_two_entries(dir: &std::path::Path) -> Tracker {
+    let store = DataStore::new(dir.join("tracker_edit.json"));
+    let tracker = Tracker::new(store);
+
+    use time_checker::data::TimeEntry;
+    let entries = vec![
+        TimeEntry {
+            id: 1,
+            task: "設計".to_string(),
+            start: Local.with_ymd_and_hms(2025, 11, 5, 9, 0, 0).unwrap(),
+            end: Some(Local.with_ymd_and_hms(2025, 11, 5, 10, 0, 0).unwrap()),
+            note: Some("初版".to_string()),
+            ..Default::default()
+        },
+        TimeEntry {
+            id: 2,
+            task: "実装".to_string(),
+            start: Local.with_ymd_and_hms(2025, 11, 5, 10, 0, 0).unwrap(),
+            end: Some(Local.with_ymd_and_hms(2025, 11, 5, 11, 0, 0).unwrap()),
+            note: None,
+            ..Default::default()
+        },
+    ];
+    tracker.store().save(&entries).expect("保存に失敗");
+    tracker
+}
+
+#[test]
+fn test_tracker_edit_entry() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let tracker = tracker_with_two_entries(dir.path());
+
+    use time_checker::tracker::EntryEdit;
+    let updated = tracker
+        .edit_entry(
+            1,
+            EntryEdit {
+                task: Some("設計レビュー".to_string()),
+                note: Some(None),
+                start: Some(Local.with_ymd_and_hms(2025, 11, 5, 8, 30, 0).unwrap()),
+                ..Default::default()
+            },
+        )
+        .expect("編集に失敗");
+
+    assert_eq!(updated.task, "設計レビュー");
+    assert_eq!(updated.note, None);
+
+    let stored = tracker.store().get_entry(1).expect("取得に失敗").unwrap();
+    assert_eq!(stored, updated);
+    assert_eq!(stored.start, Local.with_ymd_and_hms(2025, 11, 5, 8, 30, 0).unwrap());
+}
+
+#[test]
+fn test_tracker_edit_entry_rejects_invalid_changes() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let tracker = tracker_with_two_entries(dir.path());
+
+    use time_checker::error::TimeCheckerError;
+    use time_checker::tracker::EntryEdit;
+
+    // 終了時刻が開始時刻より前
+    let result = tracker.edit_entry(
+        1,
+        EntryEdit {
+            end: Some(Local.with_ymd_and_hms(2025, 11, 5, 8, 0, 0).unwrap()),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(result, Err(TimeCheckerError::InvalidTimeRange(_))));
+
+    // 隣のエントリと重なる
+    let result = tracker.edit_entry(
+        1,
+        EntryEdit {
+            end: Some(Local.with_ymd_and_hms(2025, 11, 5, 10, 30, 0).unwrap()),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(result, Err(TimeCheckerError::OverlappingEntry(2))));
+
+    // 存在しないID
+    let result = tracker.edit_entry(99, EntryEdit::default());
+    assert!(matches!(result, Err(TimeCheckerError::EntryNotFound(99))));
+
+    // 失敗した編集は保存されない
+    let stored = tracker.store().get_entry(1).expect("取得に失敗").unwrap();
+    assert_eq!(stored.end, Some(Local.with_ymd_and_hms(2025, 11, 5, 10, 0, 0).unwrap()));
+}
+
+#[test]
+fn test_tracker_delete_entry() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let tracker = tracker_with_two_entries(dir.path());
+
+    let removed = tracker.delete_entry(1).expect("削除に失敗");
+    assert_eq!(removed.task, "設計");
+
+    let entries = tracker.store().load().expect("読み込みに失敗");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, 2);
+
+    // 削除済みのIDはエラー
+    assert!(tracker.delete_entry(1).is_err());
+}
+
+#[test]
+fn test_tracker_does_not_reuse_deleted_ids() {
+    let dir = tempdir().expect("一時ディレクトリの作成に失敗");
+    let tracker = tracker_with_two_entries(dir.path());
+
+    // 最後のエントリを削除しても、そのIDは新しいエントリに使わない
+    tracker.delete_entry(2).expect("削除に失敗");
+    let added = tracker
+        .add_entry(
+            "レビュー".to_string(),
+            None,
+            Local.with_ymd_and_hms(2025, 11, 5, 13, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2025, 11, 5, 14, 0, 0).unwrap(),
+        )
+        .expect("追加に失敗");
+    assert_eq!(added.id, 3);
+
+    tracker.delete_entry(3).expect("削除に失敗");
+    let reopened = DataStore::new(dir.path().join("tracker_edit.json"));
+    assert_eq!(reopened.load_entries().unwrap().next_id(), 4);
+}
+
+/// タグ付きのエントリを3件持つTracker
+/// 9:00-10:00 実装 +backend +urgent / 10:00-10:30 会議 / 10:30-11:00 画面 +frontend
+fn tracker_with_tagged_entries() -> Tracker<MemoryStorage> {
+    let tracker = Tracker::new(MemoryStorage::new());
+    let at = |h, m| Local.with_ymd_and_hms(2025, 11, 5, h, m, 0).unwrap();
+    let tags = |names: &[&str]| names.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+    tracker
+        .add_tagged_entry("実装".to_string(), None, tags(&["backend", "urgent", "backend"]), at(9, 0), at(10, 0))
+        .expect("追加に失敗");
+    tracker.add_entry("会議".to_string(), None, at(10, 0), at(10, 30)).expect("追加に失敗");
+    tracker
+        .add_tagged_entry("画面".to_string(), None, tags(&["frontend"]), at(10, 30), at(11, 0))
+        .expect("追加に失敗");
+    tracker
+}
+
+#[test]
+fn test_tracker_filters_by_tag() {
+    let tracker = tracker_with_tagged_entries();
+    let range = DateRange::single(NaiveDate::from_ymd_opt(2025, 11, 5).unwrap());
+
+    // 重複したタグは1つにまとめられる
+    let entries = tracker.list_entries(range).expect("取得に失敗");
+    assert_eq!(entries[0].tags, vec!["backend", "urgent"]);
+
+    let tagged = tracker.list_tagged_entries(range, &["urgent".to_string(), "frontend".to_string()]).unwrap();
+    assert_eq!(tagged.iter().map(|e| e.id).collect::<Vec<_>>(), vec![1, 3]);
+
+    let summary = tracker.get_tagged_summary(range, &["backend".to_string()]).unwrap();
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary.get("実装").unwrap().as_secs(), 3600);
+}
+
+#[test]
+fn test_tracker_tag_summary() {
+    let tracker = tracker_with_tagged_entries();
+    let range = DateRange::single(NaiveDate::from_ymd_opt(2025, 11, 5).unwrap());
+
+    let summary = tracker.get_tag_summary(range, &[], &TaskFilter::none()).expect("集計に失敗");
+    assert_eq!(summary.tags.get("backend").unwrap().as_secs(), 3600);
+    assert_eq!(summary.tags.get("urgent").unwrap().as_secs(), 3600);
+    assert_eq!(summary.tags.get("frontend").unwrap().as_secs(), 1800);
+    assert_eq!(summary.untagged.as_secs(), 1800);
+
+    // 除外フィルタに一致するタスクはタグごとには集計せず、別枠に振り分ける
+    let exclude = TaskFilter::parse(&["会議"]).unwrap();
+    let summary = tracker.get_tag_summary(range, &[], &exclude).unwrap();
+    assert!(summary.untagged.is_zero());
+    assert_eq!(summary.excluded.get("会議").unwrap().as_secs(), 1800);
+    assert_eq!(summary.excluded_total().as_secs(), 1800);
+
+    // タグで絞り込むと、そのタグを持つエントリの他のタグも集計される
+    let summary = tracker.get_tag_summary(range, &["urgent".to_string()], &TaskFilter::none()).unwrap();
+    assert_eq!(summary.tags.len(), 2);
+    assert!(summary.tags.contains_key("backend"));
+}
+
+#[test]
+fn test_tracker_edit_tags() {
+    let tracker = tracker_with_tagged_entries();
+
+    let edit = EntryEdit { tags: Some(vec!["ops".to_string()]), ..Default::default() };
+    assert_eq!(tracker.edit_entry(2, edit).unwrap().tags, vec!["ops"]);
+
+    let edit = EntryEdit { tags: Some(Vec::new()), ..Default::default() };
+    assert!(tracker.edit_entry(1, edit).unwrap().tags.is_empty());
+}